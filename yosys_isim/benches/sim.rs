@@ -0,0 +1,202 @@
+//! Compares the simulation engines on a synthetic pipeline.
+//!
+//! `cargo bench -p yosys_isim --features jit`
+
+use serde_json::Value;
+use serde_json::json;
+use std::time::Duration;
+use std::time::Instant;
+use yosys_isim::common::Random;
+use yosys_isim::json;
+use yosys_isim::json::parse_netlist;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Scheduler;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+const WIDTH: usize = 32;
+const NUM_CYCLES: usize = 2_000;
+
+struct NetlistBuilder {
+    next_bit: usize,
+    cells: serde_json::Map<String, Value>,
+}
+
+impl NetlistBuilder {
+    fn bits(&mut self, width: usize) -> Vec<usize> {
+        let bits: Vec<usize> = (self.next_bit..self.next_bit + width).collect();
+        self.next_bit += width;
+        bits
+    }
+
+    fn cell(&mut self, r#type: &str, connections: Value, parameters: Value) {
+        let directions: serde_json::Map<String, Value> = connections
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|name| {
+                let direction = match name.as_str() {
+                    "Y" | "Q" => "output",
+                    _ => "input",
+                };
+                (name.clone(), json!(direction))
+            })
+            .collect();
+
+        self.cells.insert(
+            format!("{}${}", r#type, self.cells.len()),
+            json!({
+                "type": r#type,
+                "port_directions": directions,
+                "connections": connections,
+                "parameters": parameters,
+            }),
+        );
+    }
+
+    fn unary(&mut self, r#type: &str, a: &[usize]) -> Vec<usize> {
+        let y = self.bits(a.len());
+        self.cell(
+            r#type,
+            json!({ "A": a, "Y": y }),
+            json!({ "A_SIGNED": 0, "A_WIDTH": a.len(), "Y_WIDTH": y.len() }),
+        );
+        y
+    }
+
+    fn binary(&mut self, r#type: &str, a: &[usize], b: &[usize]) -> Vec<usize> {
+        let y = self.bits(a.len());
+        self.cell(
+            r#type,
+            json!({ "A": a, "B": b, "Y": y }),
+            json!({
+                "A_SIGNED": 0, "A_WIDTH": a.len(),
+                "B_SIGNED": 0, "B_WIDTH": b.len(),
+                "Y_WIDTH": y.len(),
+            }),
+        );
+        y
+    }
+
+    fn dff(&mut self, clk: &[usize], d: &[usize], q: &[usize]) {
+        self.cell(
+            "$dff",
+            json!({ "CLK": clk, "D": d, "Q": q }),
+            json!({ "CLK_POLARITY": 1, "WIDTH": d.len() }),
+        );
+    }
+}
+
+/// `num_stages` registers, each updated from a mix of itself and its predecessor
+fn pipeline(num_stages: usize) -> Module {
+    let mut builder = NetlistBuilder {
+        next_bit: 2,
+        cells: serde_json::Map::new(),
+    };
+
+    let clk = builder.bits(1);
+    let a = builder.bits(WIDTH);
+
+    let mut prev = a.clone();
+    for _ in 0..num_stages {
+        let q = builder.bits(WIDTH);
+        let sum = builder.binary("$add", &q, &prev);
+        let not_q = builder.unary("$not", &q);
+        let mix = builder.binary("$and", &sum, &not_q);
+        let d = builder.binary("$or", &mix, &prev);
+        builder.dff(&clk, &d, &q);
+        prev = q;
+    }
+
+    let json_netlist: json::Netlist = serde_json::from_value(json!({
+        "modules": {
+            "Pipeline": {
+                "ports": {
+                    "clk": { "direction": "input", "bits": clk },
+                    "a": { "direction": "input", "bits": a },
+                    "y": { "direction": "output", "bits": prev },
+                },
+                "cells": builder.cells,
+                "netnames": {},
+            }
+        }
+    }))
+    .unwrap();
+
+    parse_netlist(&json_netlist).unwrap().pop().unwrap()
+}
+
+fn run(module: &Module, config: SimConfig) -> Duration {
+    let clk = module.in_ports.iter().find(|it| it.name == "clk").unwrap();
+    let a = module.in_ports.iter().find(|it| it.name == "a").unwrap();
+    let mut sim = Sim::with_config(module, config);
+
+    let mut random = Random::new(1);
+    let start = Instant::now();
+    for _ in 0..NUM_CYCLES {
+        let logics = random.logics(WIDTH);
+
+        sim.set_dynamic(a, &logics[..]);
+        sim.set_dynamic(clk, &[0]);
+        sim.simulate().unwrap();
+        sim.set_dynamic(clk, &[1]);
+        sim.simulate().unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let configs = [
+        (
+            "interpreter, full sweep",
+            SimConfig {
+                engine: Engine::Interpreter,
+                scheduler: Scheduler::FullSweep,
+                ..Default::default()
+            },
+        ),
+        (
+            "interpreter, event driven",
+            SimConfig {
+                engine: Engine::Interpreter,
+                scheduler: Scheduler::EventDriven,
+                ..Default::default()
+            },
+        ),
+        (
+            "tape",
+            SimConfig {
+                engine: Engine::Tape,
+                ..Default::default()
+            },
+        ),
+        #[cfg(feature = "jit")]
+        (
+            "jit",
+            SimConfig {
+                engine: Engine::Jit,
+                ..Default::default()
+            },
+        ),
+    ];
+
+    println!("{} cycles of a {} bit pipeline", NUM_CYCLES, WIDTH);
+    for num_stages in [4, 32, 128] {
+        let module = pipeline(num_stages);
+
+        // the speedups are relative to the printed full sweep
+        let mut baseline = None;
+        for (name, config) in configs.iter() {
+            let duration = run(&module, config.clone());
+            let baseline = *baseline.get_or_insert(duration);
+            println!(
+                "stages: {:>4} | {:<26} | {:>10.2?} | {:>5.2}x",
+                num_stages,
+                name,
+                duration,
+                baseline.as_secs_f64() / duration.as_secs_f64()
+            );
+        }
+    }
+}
@@ -11,6 +11,12 @@ impl<T> EmptyBuffer<T> {
     }
 }
 
+impl<T> Default for EmptyBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: Clone> EmptyBuffer<T> {
     pub fn prepare(mut self, len: usize, init: T) -> Buffer<T> {
         self.buffer.resize(len, init);
@@ -1,15 +1,184 @@
+use crate::json::SupportReport;
+use crate::model::PortDirection;
+use crate::sim::Clock;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum SimError {
-    #[error("IOError")]
+    #[error("io error: {0}")]
     IOError(#[from] std::io::Error),
-    #[error("SerdeError")]
+    #[error("serde error: {0}")]
     SerdeError(#[from] serde_json::Error),
-    #[error("JsonError [{msg:?}]")]
+    #[error("json error: {msg}")]
     JsonError { msg: String },
-    #[error("SimError [{msg:?}]")]
-    SimError { msg: String },
-    #[error("IllegalState")]
+    #[error("could not find {label} [{name}]")]
+    NotFound { label: &'static str, name: String },
+    #[error("unsupported cell type [{cell_type}] of cell [{cell}] in module [{module}]")]
+    UnsupportedCellType {
+        module: String,
+        cell: String,
+        cell_type: String,
+    },
+    #[error("could not find port [{port}] {}", location(.module, .cell))]
+    PortNotFound {
+        module: String,
+        cell: Option<String>,
+        port: String,
+    },
+    #[error(
+        "wrong width on port [{port}] {}, expected [{expected}] but actual is [{actual}]",
+        location(.module, .cell)
+    )]
+    PortWidthMismatch {
+        module: String,
+        cell: Option<String>,
+        port: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("port [{port}] {} is driven by a constant", location(.module, .cell))]
+    ConstantPort {
+        module: String,
+        cell: Option<String>,
+        port: String,
+    },
+    #[error("unknown parameter [{parameter}={value}] on cell [{cell}] in module [{module}]")]
+    UnknownParameter {
+        module: String,
+        cell: String,
+        parameter: String,
+        value: String,
+    },
+    #[error(
+        "illegal value of parameter [{parameter}={value}] on cell [{cell}] in module [{module}]"
+    )]
+    IllegalParameter {
+        module: String,
+        cell: String,
+        parameter: String,
+        value: String,
+    },
+    #[error("netlist contains unsupported cells or parameters\n{report}")]
+    UnsupportedNetlist { report: SupportReport },
+    #[error("illegal state: {msg}")]
     IllegalState { msg: String },
+    #[error("jit error: {msg}")]
+    JitError { msg: String },
+    #[error(
+        "snapshot of module [{snapshot_module}] with [{snapshot_num_wires}] wires does not match module [{module}] with [{num_wires}] wires"
+    )]
+    SnapshotMismatch {
+        module: String,
+        num_wires: usize,
+        snapshot_module: String,
+        snapshot_num_wires: usize,
+    },
+    #[error(
+        "wrong width on net [{net}] of module [{module}], expected [{expected}] but actual is [{actual}]"
+    )]
+    NetWidthMismatch {
+        module: String,
+        net: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error(
+        "illegal clock {clock:?} on port [{port}], the period must be at least [2] and the duty cycle in [0..=1]"
+    )]
+    IllegalClock { port: String, clock: Clock },
+    #[error("the history is not recorded, see `SimConfig::history`")]
+    HistoryDisabled,
+    #[error("frame [{frame}] is not in the history of frames [{first_frame}..={last_frame}]")]
+    FrameNotInHistory {
+        frame: usize,
+        first_frame: usize,
+        last_frame: usize,
+    },
+    #[error(
+        "port [{port}] of module [{module}] has [{width}] bits, more than fit into [{max_width}]"
+    )]
+    PortTooWide {
+        module: String,
+        port: String,
+        width: usize,
+        max_width: usize,
+    },
+    #[error("illegal literal [{literal}]: {msg}")]
+    IllegalLiteral { literal: String, msg: &'static str },
+    #[error("port [{port}] of module [{module}] is an {direction}")]
+    WrongPortDirection {
+        module: String,
+        port: String,
+        direction: PortDirection,
+    },
+    #[error("illegal test vectors in line [{line}]: {msg}")]
+    IllegalTestVectors { line: usize, msg: String },
+    #[error(
+        "the in ports of module [{module}] have [{width}] bits, more than [{max_width}] to enumerate"
+    )]
+    TooManyInputBits {
+        module: String,
+        width: usize,
+        max_width: usize,
+    },
+    #[error("module [{module}] is not combinational, cell [{cell}] is sequential")]
+    NotCombinational { module: String, cell: String },
+    #[error("module [{module}] has no out ports")]
+    NoOutPorts { module: String },
+    #[error(
+        "expected [{expected}] values for the out ports of module [{module}] but got [{actual}]"
+    )]
+    ExpectedLengthMismatch {
+        module: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("illegal constraint on port [{port}] of module [{module}]: {msg}")]
+    IllegalConstraint {
+        module: String,
+        port: String,
+        msg: String,
+    },
+}
+
+impl SimError {
+    /// A stable, machine readable identifier of the error kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SimError::IOError(_) => "IO_ERROR",
+            SimError::SerdeError(_) => "SERDE_ERROR",
+            SimError::JsonError { .. } => "JSON_ERROR",
+            SimError::NotFound { .. } => "NOT_FOUND",
+            SimError::UnsupportedCellType { .. } => "UNSUPPORTED_CELL_TYPE",
+            SimError::PortNotFound { .. } => "PORT_NOT_FOUND",
+            SimError::PortWidthMismatch { .. } => "PORT_WIDTH_MISMATCH",
+            SimError::ConstantPort { .. } => "CONSTANT_PORT",
+            SimError::UnknownParameter { .. } => "UNKNOWN_PARAMETER",
+            SimError::IllegalParameter { .. } => "ILLEGAL_PARAMETER",
+            SimError::UnsupportedNetlist { .. } => "UNSUPPORTED_NETLIST",
+            SimError::IllegalState { .. } => "ILLEGAL_STATE",
+            SimError::JitError { .. } => "JIT_ERROR",
+            SimError::SnapshotMismatch { .. } => "SNAPSHOT_MISMATCH",
+            SimError::NetWidthMismatch { .. } => "NET_WIDTH_MISMATCH",
+            SimError::IllegalClock { .. } => "ILLEGAL_CLOCK",
+            SimError::HistoryDisabled => "HISTORY_DISABLED",
+            SimError::FrameNotInHistory { .. } => "FRAME_NOT_IN_HISTORY",
+            SimError::PortTooWide { .. } => "PORT_TOO_WIDE",
+            SimError::IllegalLiteral { .. } => "ILLEGAL_LITERAL",
+            SimError::WrongPortDirection { .. } => "WRONG_PORT_DIRECTION",
+            SimError::IllegalTestVectors { .. } => "ILLEGAL_TEST_VECTORS",
+            SimError::TooManyInputBits { .. } => "TOO_MANY_INPUT_BITS",
+            SimError::NotCombinational { .. } => "NOT_COMBINATIONAL",
+            SimError::NoOutPorts { .. } => "NO_OUT_PORTS",
+            SimError::ExpectedLengthMismatch { .. } => "EXPECTED_LENGTH_MISMATCH",
+            SimError::IllegalConstraint { .. } => "ILLEGAL_CONSTRAINT",
+        }
+    }
+}
+
+fn location(module: &str, cell: &Option<String>) -> String {
+    match cell {
+        Some(cell) => format!("of cell [{}] in module [{}]", cell, module),
+        None => format!("of module [{}]", module),
+    }
 }
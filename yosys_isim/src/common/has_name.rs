@@ -17,8 +17,9 @@ impl<T: HasName, Item: Deref<Target = T>, Iter: Iterator<Item = Item>> FindByNam
 
     fn find_by_name(mut self, name: &str) -> Result<Self::Item, SimError> {
         self.find(|it| it.name().eq(name))
-            .ok_or_else(|| SimError::SimError {
-                msg: format!("could not find {} [{}]", T::LABEL, name),
+            .ok_or_else(|| SimError::NotFound {
+                label: T::LABEL,
+                name: name.to_string(),
             })
     }
 }
@@ -2,8 +2,10 @@ mod collections;
 mod error;
 mod has_name;
 mod buffer;
+mod random;
 
 pub use collections::*;
 pub use error::*;
 pub use has_name::*;
-pub use buffer::*;
\ No newline at end of file
+pub use buffer::*;
+pub use random::*;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+
+/// SplitMix64, so random values are reproducible from their seed without extra dependencies.
+/// Used for random register values and random stimulus.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// a number in `0..bound`, `bound` must not be `0`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// `0` or `1`
+    pub fn logic(&mut self) -> Logic {
+        Logic::from(self.next_u64() >> 63)
+    }
+
+    /// `width` random bits, none of them `X`
+    pub fn logics(&mut self, width: usize) -> LogicVec {
+        (0..width.div_ceil(64))
+            .flat_map(|_| {
+                let word = self.next_u64();
+                (0..64).map(move |bit| Logic::from((word >> bit) & 1))
+            })
+            .take(width)
+            .collect()
+    }
+}
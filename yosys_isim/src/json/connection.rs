@@ -5,6 +5,7 @@ use crate::json;
 use crate::json::parse_wires;
 use crate::model::HWireOrLogic;
 use crate::model::{self};
+use serde_json::Value;
 use std::marker::PhantomData;
 
 pub(super) struct Connection<'a> {
@@ -19,36 +20,84 @@ pub(super) struct Connection<'a> {
 impl<'a> HasName for Connection<'a> {
     const LABEL: &'static str = "connection";
     fn name(&self) -> &'a str {
-        &self.name
+        self.name
     }
 }
 
-impl<'a> Connection<'a> {
-    pub(super) fn to_in_port(&self) -> Result<model::CellInPort, SimError> {
-        Ok(model::CellInPort {
-            name: self.name.to_string(),
-            wires: self.wires.clone(),
+/// The connections of a single cell, together with the names needed to report errors.
+pub(super) struct Connections<'a> {
+    pub module_name: &'a str,
+    pub cell_name: &'a str,
+    pub connections: Vec4<Connection<'a>>,
+}
+
+impl<'a> Connections<'a> {
+    pub(super) fn get(&self, name: &str) -> Result<&Connection<'a>, SimError> {
+        self.connections
+            .iter()
+            .find(|it| it.name.eq(name))
+            .ok_or_else(|| SimError::PortNotFound {
+                module: self.module_name.to_string(),
+                cell: Some(self.cell_name.to_string()),
+                port: name.to_string(),
+            })
+    }
+
+    pub(super) fn expect_width(
+        &self,
+        connection: &Connection<'a>,
+        expected: u32,
+    ) -> Result<(), SimError> {
+        if connection.width != expected {
+            return Err(SimError::PortWidthMismatch {
+                module: self.module_name.to_string(),
+                cell: Some(self.cell_name.to_string()),
+                port: connection.name.to_string(),
+                expected: expected as usize,
+                actual: connection.width as usize,
+            });
+        }
+        Ok(())
+    }
+
+    pub(super) fn to_in_port(&self, connection: &Connection<'a>) -> model::CellInPort {
+        model::CellInPort {
+            name: connection.name.to_string(),
+            wires: connection.wires.clone(),
             dir: PhantomData,
-        })
+        }
     }
 
-    pub(super) fn to_out_port(&self) -> Result<model::CellOutPort, SimError> {
+    pub(super) fn to_out_port(
+        &self,
+        connection: &Connection<'a>,
+    ) -> Result<model::CellOutPort, SimError> {
         Ok(model::CellOutPort {
-            name: self.name.to_string(),
-            wires: HWireOrLogic::only_HWires(&self.wires)?,
+            name: connection.name.to_string(),
+            wires: HWireOrLogic::only_HWires(&connection.wires).ok_or_else(|| {
+                SimError::ConstantPort {
+                    module: self.module_name.to_string(),
+                    cell: Some(self.cell_name.to_string()),
+                    port: connection.name.to_string(),
+                }
+            })?,
             dir: PhantomData,
         })
     }
 }
 
-pub(super) fn parse_connections(json_cell: &json::Cell) -> Result<Vec4<Connection>, SimError> {
+pub(super) fn parse_connections<'a>(
+    module_name: &'a str,
+    cell_name: &'a str,
+    json_cell: &'a json::Cell,
+) -> Result<Connections<'a>, SimError> {
     let mut connections: Vec4<Connection> = Vec4::new();
 
     for (name, wires) in json_cell.connections.iter() {
         connections.push(Connection {
-            name: &name,
+            name,
             direction: json::PortDirection::Input,
-            wires: parse_wires(&wires)?,
+            wires: parse_wires(wires)?,
             signed: false,
             polarity: 1,
             width: 1,
@@ -59,102 +108,134 @@ pub(super) fn parse_connections(json_cell: &json::Cell) -> Result<Vec4<Connectio
         match connections.iter_mut().find(|it| it.name.eq(name)) {
             Some(connection) => connection.direction = *direction,
             None => {
-                return Err(SimError::JsonError {
-                    msg: format!("Could not assign direction for [{}]", name.to_string()),
+                return Err(SimError::PortNotFound {
+                    module: module_name.to_string(),
+                    cell: Some(cell_name.to_string()),
+                    port: name.to_string(),
                 });
             }
         }
     }
 
+    let unknown_parameter = |parameter: &str, value: &Value| SimError::UnknownParameter {
+        module: module_name.to_string(),
+        cell: cell_name.to_string(),
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+    };
+
+    let illegal_parameter = |parameter: &str, value: &Value| SimError::IllegalParameter {
+        module: module_name.to_string(),
+        cell: cell_name.to_string(),
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+    };
+
     for (parameter, value) in json_cell.parameters.iter() {
-        let index_of_underscore = match parameter.find('_') {
-            Some(index) => index,
-            None => match parameter.as_str() {
-                "WIDTH" => continue,
-                _ => {
-                    return Err(SimError::JsonError {
-                        msg: format!(
-                            "could not recognize port name of property [{}]",
-                            parameter.to_string()
-                        ),
-                    });
-                }
-            },
+        let (connection_name, parameter_name) = match recognize_parameter(json_cell, parameter) {
+            Parameter::Ignored => continue,
+            Parameter::Connection(connection_name, parameter_name) => {
+                (connection_name, parameter_name)
+            }
+            Parameter::Unknown => return Err(unknown_parameter(parameter, value)),
         };
 
-        let connection_name = &parameter[0..index_of_underscore];
-        let parameter_name = &parameter[index_of_underscore + 1..];
-
         let connection = match connections
             .iter_mut()
             .find(|it| it.name.eq(connection_name))
         {
             Some(connection) => connection,
-            None => {
-                return Err(SimError::JsonError {
-                    msg: format!(
-                        "could not find connection with name [{}]",
-                        connection_name.to_string()
-                    ),
-                });
-            }
+            None => return Err(unknown_parameter(parameter, value)),
         };
 
         match parameter_name {
-            "SIGNED" => {
-                connection.signed = match value.as_u64() {
+            ConnectionParameter::Signed => {
+                connection.signed = match parse_int_parameter(value) {
                     Some(0) => false,
                     Some(1) => true,
-                    _ => {
-                        return Err(SimError::JsonError {
-                            msg: format!(
-                                "encountered illegal SIGNED-property [{}={}]",
-                                parameter_name,
-                                value.to_string()
-                            ),
-                        });
-                    }
+                    _ => return Err(illegal_parameter(parameter, value)),
                 }
             }
-            "WIDTH" => {
-                connection.width = match value.as_u64() {
+            ConnectionParameter::Width => {
+                connection.width = match parse_int_parameter(value) {
                     Some(n) => n as u32,
-                    _ => {
-                        return Err(SimError::JsonError {
-                            msg: format!(
-                                "encountered illegal WIDTH-property [{}={}]",
-                                parameter_name,
-                                value.to_string()
-                            ),
-                        });
-                    }
+                    _ => return Err(illegal_parameter(parameter, value)),
                 }
             }
-            "POLARITY" => {
-                connection.polarity = match value.as_u64() {
+            ConnectionParameter::Polarity => {
+                connection.polarity = match parse_int_parameter(value) {
                     Some(n) => n as u32,
-                    _ => {
-                        return Err(SimError::JsonError {
-                            msg: format!(
-                                "encountered illegal POLARITY-property [{}={}]",
-                                parameter_name,
-                                value.to_string()
-                            ),
-                        });
-                    }
+                    _ => return Err(illegal_parameter(parameter, value)),
                 }
             }
-            _ => {
-                return Err(SimError::JsonError {
-                    msg: format!(
-                        "encountered unknown property [{}={}]",
-                        parameter_name,
-                        value.to_string()
-                    ),
-                });
-            }
         };
     }
 
-    Ok(connections)
+    Ok(Connections {
+        module_name,
+        cell_name,
+        connections,
+    })
+}
+
+/// Yosys 0.9 and `write_json -compat-int` write integer parameters as numbers, later versions
+/// as strings of bits with the most significant bit first. An empty string, like the
+/// `TRG_POLARITY` of a `$check` cell without trigger, is `0`.
+pub(super) fn parse_int_parameter(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(bits) if bits.chars().all(|it| it == '0' || it == '1') => {
+            match bits.trim_start_matches('0') {
+                "" => Some(0),
+                bits => u64::from_str_radix(bits, 2).ok(),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum ConnectionParameter {
+    Signed,
+    Width,
+    Polarity,
+}
+
+pub(super) enum Parameter<'p> {
+    /// a cell-wide parameter that does not need to be applied to a connection
+    Ignored,
+    /// a parameter like `A_WIDTH` that belongs to the connection `A`
+    Connection(&'p str, ConnectionParameter),
+    Unknown,
+}
+
+pub(super) fn recognize_parameter<'p>(json_cell: &json::Cell, parameter: &'p str) -> Parameter<'p> {
+    // read by the parser of the `$check` cell itself
+    if let json::CellType::Check = json_cell.r#type
+        && let "FLAVOR" | "PRIORITY" | "FORMAT" | "TRG_ENABLE" = parameter
+    {
+        return Parameter::Ignored;
+    }
+
+    let index_of_underscore = match parameter.find('_') {
+        Some(index) => index,
+        None => match parameter {
+            "WIDTH" => return Parameter::Ignored,
+            _ => return Parameter::Unknown,
+        },
+    };
+
+    let connection_name = &parameter[0..index_of_underscore];
+    let parameter_name = &parameter[index_of_underscore + 1..];
+
+    if !json_cell.connections.contains_key(connection_name) {
+        return Parameter::Unknown;
+    }
+
+    match parameter_name {
+        "SIGNED" => Parameter::Connection(connection_name, ConnectionParameter::Signed),
+        "WIDTH" => Parameter::Connection(connection_name, ConnectionParameter::Width),
+        "POLARITY" => Parameter::Connection(connection_name, ConnectionParameter::Polarity),
+        _ => Parameter::Unknown,
+    }
 }
@@ -20,6 +20,8 @@ pub struct Module {
 pub struct Port {
     pub direction: PortDirection,
     pub bits: Vec4<Value>,
+    #[serde(default)]
+    pub signed: u8,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
@@ -36,10 +38,12 @@ pub struct Cell {
     pub port_directions: HashMap<String, PortDirection>,
     pub connections: HashMap<String, Vec4<Value>>,
     pub parameters: HashMap<String, Value>,
+    #[serde(default)]
+    pub attributes: HashMap<String, Value>,
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum CellType {
     #[serde(rename = "$and")]
     AND,
@@ -80,9 +84,59 @@ pub enum CellType {
     SyntAOI3,
     #[serde(rename = "$_OAI3_")]
     SyntOAI3,
+    // --------------------------------
+    #[serde(rename = "$assert")]
+    Assert,
+    #[serde(rename = "$assume")]
+    Assume,
+    #[serde(rename = "$cover")]
+    Cover,
+    #[serde(rename = "$live")]
+    Live,
+    #[serde(rename = "$fair")]
+    Fair,
+    #[serde(rename = "$check")]
+    Check,
+    // --------------------------------
+    #[serde(untagged)]
+    Unsupported(String),
+}
+
+impl CellType {
+    /// the type name as written by Yosys, e.g. `$and`
+    pub fn name(&self) -> &str {
+        match self {
+            CellType::AND => "$and",
+            CellType::OR => "$or",
+            CellType::NOT => "$not",
+            CellType::DFF => "$dff",
+            CellType::Add => "$add",
+            CellType::SyntAND => "$_AND_",
+            CellType::SyntOR => "$_OR_",
+            CellType::SyntXOR => "$_XOR_",
+            CellType::SyntNAND => "$_NAND_",
+            CellType::SyntNOR => "$_NOR_",
+            CellType::SyntXNOR => "$_XNOR_",
+            CellType::SyntAND_NOT => "$_ANDNOT_",
+            CellType::SyntOR_NOT => "$_ORNOT_",
+            CellType::SyntNOT => "$_NOT_",
+            CellType::SyntDFFPos => "$_DFF_P_",
+            CellType::SyntAOI3 => "$_AOI3_",
+            CellType::SyntOAI3 => "$_OAI3_",
+            CellType::Assert => "$assert",
+            CellType::Assume => "$assume",
+            CellType::Cover => "$cover",
+            CellType::Live => "$live",
+            CellType::Fair => "$fair",
+            CellType::Check => "$check",
+            CellType::Unsupported(name) => name,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Net {
-    pub bits: Vec4<usize>,
+    pub bits: Vec4<Value>,
+    #[serde(default)]
+    pub attributes: HashMap<String, Value>,
 }
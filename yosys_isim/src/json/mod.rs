@@ -1,8 +1,11 @@
 mod connection;
+#[allow(clippy::module_inception)]
 mod json;
 mod parser;
+mod report;
 
 #[allow(unused)]
 pub use connection::*;
 pub use json::*;
 pub use parser::*;
+pub use report::*;
@@ -1,9 +1,9 @@
-use crate::common::FindByName;
 use crate::common::SimError;
 use crate::common::Vec4;
 use crate::json;
-use crate::json::Connection;
+use crate::json::check_netlist;
 use crate::json::parse_connections;
+use crate::json::parse_int_parameter;
 use crate::model;
 use crate::model::HWireOrLogic;
 use crate::model::ModuleInPort;
@@ -23,6 +23,22 @@ pub fn parse_modules_from_file(file_name: &str) -> Result<Vec<model::Module>, Si
     )?))?)
 }
 
+/// Like [parse_modules_from_file], but reports all unsupported cell types and
+/// unknown parameters of the netlist at once instead of failing on the first one.
+pub fn parse_modules_from_file_checked(file_name: &str) -> Result<Vec<model::Module>, SimError> {
+    parse_netlist_checked(&serde_json::from_reader(BufReader::new(File::open(
+        file_name,
+    )?))?)
+}
+
+pub fn parse_netlist_checked(json_netlist: &json::Netlist) -> Result<Vec<model::Module>, SimError> {
+    let report = check_netlist(json_netlist);
+    if !report.is_empty() {
+        return Err(SimError::UnsupportedNetlist { report });
+    }
+    parse_netlist(json_netlist)
+}
+
 pub fn parse_netlist(json_module: &json::Netlist) -> Result<Vec<model::Module>, SimError> {
     json_module
         .modules
@@ -34,12 +50,29 @@ pub fn parse_netlist(json_module: &json::Netlist) -> Result<Vec<model::Module>,
 #[rustfmt::skip]
 fn parse_module(name: &str, json_module: &json::Module) -> Result<model::Module, SimError> {
     let mut cells: Vec<model::Cell> = Vec::new();
+    let mut formal_cells: Vec<model::FormalCell> = Vec::new();
     let mut in_ports: Vec4<model::ModuleInPort> = Vec4::new();
     let mut out_ports: Vec4<model::ModuleOutPort> = Vec4::new();
+    let mut ports: Vec<model::PortInfo> = Vec::new();
 
     for (port_name, json_port) in json_module.ports.iter() {
+        let wires = HWireOrLogic::only_HWires(&parse_wires(&json_port.bits)?).ok_or_else(|| {
+            SimError::ConstantPort {
+                module: name.to_string(),
+                cell: None,
+                port: port_name.to_string(),
+            }
+        })?;
         let name: String = port_name.to_string();
-        let wires = HWireOrLogic::only_HWires(&parse_wires(&json_port.bits)?)?;
+        ports.push(model::PortInfo {
+            name: name.clone(),
+            direction: match json_port.direction {
+                json::PortDirection::Input => model::PortDirection::Input,
+                json::PortDirection::Output => model::PortDirection::Output,
+            },
+            width: wires.len(),
+            signed: json_port.signed != 0,
+        });
         match json_port.direction {
             json::PortDirection::Input => in_ports.push(ModuleInPort {
                 name,
@@ -53,73 +86,153 @@ fn parse_module(name: &str, json_module: &json::Module) -> Result<model::Module,
             }),
         };
     }
+    ports.sort_by(|a, b| a.name.cmp(&b.name));
 
 
     for (cell_name, json_cell) in json_module.cells.iter() {
-        let cell: model::Cell =  match json_cell.r#type {
+        let cell: model::Cell =  match &json_cell.r#type {
             json::CellType::AND | json::CellType::SyntAND => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::AND)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::AND)?
             }
             json::CellType::OR | json::CellType::SyntOR => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::OR)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::OR)?
             }
             json::CellType::NOT | json::CellType::SyntNOT => {
-                parse_unary(cell_name, json_cell, ("A", "Y"), ops::UnaryOp::NOT)?
+                parse_unary(name, cell_name, json_cell, ("A", "Y"), ops::UnaryOp::NOT)?
             }
             json::CellType::DFF => {
-                parse_flipflop(cell_name, json_cell, ("CLK", "D", "Q"), None)?
+                parse_flipflop(name, cell_name, json_cell, ("CLK", "D", "Q"), None)?
             }
             json::CellType::Add => {
-                parse_add(cell_name, json_cell, ("A", "B", "Y"))?
+                parse_add(name, cell_name, json_cell, ("A", "B", "Y"))?
             }
             json::CellType::SyntDFFPos => {
-                parse_flipflop(cell_name, json_cell, ("C", "D", "Q"), Some(Edge::POSITIVE))?
+                parse_flipflop(name, cell_name, json_cell, ("C", "D", "Q"), Some(Edge::POSITIVE))?
             }
             json::CellType::SyntNAND => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::NAND)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::NAND)?
             }
             json::CellType::SyntNOR => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::NOR)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::NOR)?
             }
             json::CellType::SyntXOR => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::XOR)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::XOR)?
             }
             json::CellType::SyntXNOR => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::XNOR)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::XNOR)?
             }
             json::CellType::SyntAND_NOT => {
-                parse_binary(cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::AND_NOT)?
+                parse_binary(name, cell_name, json_cell, ("A", "B", "Y"), ops::BinaryOp::AND_NOT)?
             }
             json::CellType::SyntOR_NOT => {
-                parse_binary(cell_name, json_cell, ("A", "B",  "Y"), ops::BinaryOp::OR_NOT)?
+                parse_binary(name, cell_name, json_cell, ("A", "B",  "Y"), ops::BinaryOp::OR_NOT)?
             }
 
             json::CellType::SyntAOI3 => {
-                parse_ternary(cell_name, json_cell, ("A", "B", "C", "Y"), ops::TernaryOp::AND_OR_INV)?
+                parse_ternary(name, cell_name, json_cell, ("A", "B", "C", "Y"), ops::TernaryOp::AND_OR_INV)?
             }
             json::CellType::SyntOAI3 => {
-                parse_ternary(cell_name, json_cell, ("A", "B", "C", "Y"), ops::TernaryOp::OR_AND_INV)?
+                parse_ternary(name, cell_name, json_cell, ("A", "B", "C", "Y"), ops::TernaryOp::OR_AND_INV)?
+            }
+            json::CellType::Assert => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, Some(model::FormalKind::Assert))?);
+                continue;
+            }
+            json::CellType::Assume => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, Some(model::FormalKind::Assume))?);
+                continue;
+            }
+            json::CellType::Cover => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, Some(model::FormalKind::Cover))?);
+                continue;
+            }
+            json::CellType::Live => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, Some(model::FormalKind::Live))?);
+                continue;
+            }
+            json::CellType::Fair => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, Some(model::FormalKind::Fair))?);
+                continue;
+            }
+            json::CellType::Check => {
+                formal_cells.push(parse_formal(name, cell_name, json_cell, None)?);
+                continue;
+            }
+            json::CellType::Unsupported(cell_type) => {
+                return Err(SimError::UnsupportedCellType {
+                    module: name.to_string(),
+                    cell: cell_name.to_string(),
+                    cell_type: cell_type.to_string(),
+                });
             }
         };
 
         cells.push(cell);
     }
 
+    let mut nets: Vec<model::Net> = Vec::new();
+    for (net_name, json_net) in json_module.netnames.iter() {
+        let wires = parse_wires(&json_net.bits)?;
+        let init = match json_net.attributes.get("init") {
+            Some(json_init) => Some(parse_init(json_init, wires.len())?),
+            None => None,
+        };
+        nets.push(model::Net {
+            name: net_name.to_string(),
+            wires,
+            init,
+        });
+    }
+    nets.sort_by(|a, b| a.name.cmp(&b.name));
+    formal_cells.sort_by(|a, b| a.name.cmp(&b.name));
+
     Ok(model::Module {
         name: name.to_string(),
         cells,
         in_ports,
         out_ports,
+        ports,
+        nets,
+        formal_cells,
     })
 }
 
+/// Yosys writes constant attributes as strings of bits with the most significant bit first.
+/// Values that are shorter than the net are extended with `0`.
+fn parse_init(json_init: &Value, width: usize) -> Result<Vec4<Logic>, SimError> {
+    let bits: String = match json_init {
+        Value::String(bits) => bits.clone(),
+        Value::Number(number) if number.is_u64() => format!("{:b}", number.as_u64().unwrap()),
+        _ => {
+            return Err(SimError::JsonError {
+                msg: format!("illegal init attribute [{}]", json_init),
+            });
+        }
+    };
+
+    let mut init: Vec4<Logic> = smallvec![Logic::_0; width];
+    for (logic, bit) in init.iter_mut().zip(bits.chars().rev()) {
+        *logic = match bit {
+            '0' => Logic::_0,
+            '1' => Logic::_1,
+            'x' | 'z' => Logic::X,
+            _ => {
+                return Err(SimError::JsonError {
+                    msg: format!("illegal init attribute [{}]", json_init),
+                });
+            }
+        };
+    }
+    Ok(init)
+}
+
 #[allow(non_snake_case)]
 pub(super) fn parse_wires(json_wires: &Vec4<Value>) -> Result<Vec4<model::HWireOrLogic>, SimError> {
     let mut wires = smallvec![model::HWireOrLogic::Logic( Logic::X) ; json_wires.len()];
     for i in 0..wires.len() {
         wires[i] = match &json_wires[i] {
             Value::Number(h_wire) => model::HWireOrLogic::HWire(h_wire.as_u64().unwrap() as usize),
-            Value::String(logic) => match Logic::from_str(&logic) {
+            Value::String(logic) => match Logic::from_str(logic) {
                 Some(logic) => model::HWireOrLogic::Logic(logic),
                 None => {
                     return Err(SimError::JsonError {
@@ -138,69 +251,65 @@ pub(super) fn parse_wires(json_wires: &Vec4<Value>) -> Result<Vec4<model::HWireO
 }
 
 fn parse_binary(
+    module_name: &str,
     cell_name: &str,
     json_cell: &json::Cell,
     connection_names: (&str, &str, &str),
     op: ops::BinaryOp,
 ) -> Result<model::Cell, SimError> {
-    let connections: Vec4<Connection<'_>> = parse_connections(json_cell)?;
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
 
-    let conn_a = connections.iter().find_by_name(connection_names.0)?;
-    let conn_b = connections.iter().find_by_name(connection_names.1)?;
-    let conn_y = connections.iter().find_by_name(connection_names.2)?;
+    let conn_a = connections.get(connection_names.0)?;
+    let conn_b = connections.get(connection_names.1)?;
+    let conn_y = connections.get(connection_names.2)?;
 
-    if conn_a.width != conn_b.width || conn_b.width != conn_y.width {
-        return Err(SimError::JsonError {
-            msg: format!("Widths not matching"),
-        });
-    }
+    connections.expect_width(conn_a, conn_y.width)?;
+    connections.expect_width(conn_b, conn_y.width)?;
 
     Ok(model::Cell::BinaryOpCell(model::BinaryOpCell {
         name: cell_name.to_string(),
         op,
         // width: conn_y.width,
-        port_a: conn_a.to_in_port()?,
-        port_b: conn_b.to_in_port()?,
-        port_y: conn_y.to_out_port()?,
+        port_a: connections.to_in_port(conn_a),
+        port_b: connections.to_in_port(conn_b),
+        port_y: connections.to_out_port(conn_y)?,
     }))
 }
 
 fn parse_unary(
+    module_name: &str,
     cell_name: &str,
     json_cell: &json::Cell,
     connection_names: (&str, &str),
     op: ops::UnaryOp,
 ) -> Result<model::Cell, SimError> {
-    let connections: Vec4<Connection<'_>> = parse_connections(json_cell)?;
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
 
-    let conn_a = connections.iter().find_by_name(connection_names.0)?;
-    let conn_y = connections.iter().find_by_name(connection_names.1)?;
+    let conn_a = connections.get(connection_names.0)?;
+    let conn_y = connections.get(connection_names.1)?;
 
     Ok(model::Cell::UnaryOpCell(model::UnaryOpCell {
         name: cell_name.to_string(),
-        op: op,
-        port_a: conn_a.to_in_port()?,
-        port_y: conn_y.to_out_port()?,
+        op,
+        port_a: connections.to_in_port(conn_a),
+        port_y: connections.to_out_port(conn_y)?,
     }))
 }
 
 fn parse_flipflop(
+    module_name: &str,
     cell_name: &str,
     json_cell: &json::Cell,
     connection_names: (&str, &str, &str),
     polarity: Option<Edge>,
 ) -> Result<model::Cell, SimError> {
-    let connections: Vec4<Connection<'_>> = parse_connections(json_cell)?;
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
 
-    let conn_clk = connections.iter().find_by_name(connection_names.0)?;
-    let conn_d = connections.iter().find_by_name(connection_names.1)?;
-    let conn_q = connections.iter().find_by_name(connection_names.2)?;
+    let conn_clk = connections.get(connection_names.0)?;
+    let conn_d = connections.get(connection_names.1)?;
+    let conn_q = connections.get(connection_names.2)?;
 
-    if conn_d.width != conn_q.width {
-        return Err(SimError::JsonError {
-            msg: format!("Widths not matching"),
-        });
-    }
+    connections.expect_width(conn_d, conn_q.width)?;
 
     Ok(model::Cell::DFlipFlopCell(model::DFlipFlopCell {
         name: cell_name.to_string(),
@@ -209,63 +318,140 @@ fn parse_flipflop(
         } else {
             Edge::NEGATIVE
         }),
-        port_clk: conn_clk.to_in_port()?,
-        port_d: conn_d.to_in_port()?,
-        port_q: conn_q.to_out_port()?,
+        port_clk: connections.to_in_port(conn_clk),
+        port_d: connections.to_in_port(conn_d),
+        port_q: connections.to_out_port(conn_q)?,
     }))
 }
 
 fn parse_add(
+    module_name: &str,
     cell_name: &str,
     json_cell: &json::Cell,
     connection_names: (&str, &str, &str),
 ) -> Result<model::Cell, SimError> {
-    let connections: Vec4<Connection<'_>> = parse_connections(json_cell)?;
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
 
-    let conn_a = connections.iter().find_by_name(connection_names.0)?;
-    let conn_b = connections.iter().find_by_name(connection_names.1)?;
-    let conn_y = connections.iter().find_by_name(connection_names.2)?;
+    let conn_a = connections.get(connection_names.0)?;
+    let conn_b = connections.get(connection_names.1)?;
+    let conn_y = connections.get(connection_names.2)?;
 
-    if conn_a.width != conn_b.width || conn_b.width != conn_y.width {
-        return Err(SimError::JsonError {
-            msg: format!("Widths not matching"),
-        });
-    }
+    connections.expect_width(conn_a, conn_y.width)?;
+    connections.expect_width(conn_b, conn_y.width)?;
 
     Ok(model::Cell::AddCell(model::AddCell {
         name: cell_name.to_string(),
-        port_a: conn_a.to_in_port()?,
-        port_b: conn_b.to_in_port()?,
-        port_y: conn_y.to_out_port()?,
+        port_a: connections.to_in_port(conn_a),
+        port_b: connections.to_in_port(conn_b),
+        port_y: connections.to_out_port(conn_y)?,
     }))
 }
 
 fn parse_ternary(
+    module_name: &str,
     cell_name: &str,
     json_cell: &json::Cell,
     connection_names: (&str, &str, &str, &str),
     op: ops::TernaryOp,
 ) -> Result<model::Cell, SimError> {
-    let connections: Vec4<Connection<'_>> = parse_connections(json_cell)?;
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
 
-    let conn_a = connections.iter().find_by_name(connection_names.0)?;
-    let conn_b = connections.iter().find_by_name(connection_names.1)?;
-    let conn_c = connections.iter().find_by_name(connection_names.2)?;
-    let conn_y = connections.iter().find_by_name(connection_names.3)?;
+    let conn_a = connections.get(connection_names.0)?;
+    let conn_b = connections.get(connection_names.1)?;
+    let conn_c = connections.get(connection_names.2)?;
+    let conn_y = connections.get(connection_names.3)?;
 
-    if conn_a.width != conn_b.width || conn_b.width != conn_c.width || conn_c.width != conn_y.width
-    {
-        return Err(SimError::JsonError {
-            msg: format!("Widths not matching"),
-        });
-    }
+    connections.expect_width(conn_a, conn_y.width)?;
+    connections.expect_width(conn_b, conn_y.width)?;
+    connections.expect_width(conn_c, conn_y.width)?;
 
     Ok(model::Cell::TernaryOpCell(model::TernaryOpCell {
         name: cell_name.to_string(),
-        op: op,
-        port_a: conn_a.to_in_port()?,
-        port_b: conn_b.to_in_port()?,
-        port_c: conn_c.to_in_port()?,
-        port_y: conn_y.to_out_port()?,
+        op,
+        port_a: connections.to_in_port(conn_a),
+        port_b: connections.to_in_port(conn_b),
+        port_c: connections.to_in_port(conn_c),
+        port_y: connections.to_out_port(conn_y)?,
     }))
 }
+
+/// Parses a formal cell, whose `kind` is `None` for a `$check` cell that gets it from its
+/// `FLAVOR`. A `$check` cell with `TRG_ENABLE` is clocked by a single `TRG` wire.
+fn parse_formal(
+    module_name: &str,
+    cell_name: &str,
+    json_cell: &json::Cell,
+    kind: Option<model::FormalKind>,
+) -> Result<model::FormalCell, SimError> {
+    let connections = parse_connections(module_name, cell_name, json_cell)?;
+
+    let conn_a = connections.get("A")?;
+    let conn_en = connections.get("EN")?;
+
+    for connection in [conn_a, conn_en] {
+        if connection.wires.len() != 1 {
+            return Err(SimError::PortWidthMismatch {
+                module: module_name.to_string(),
+                cell: Some(cell_name.to_string()),
+                port: connection.name.to_string(),
+                expected: 1,
+                actual: connection.wires.len(),
+            });
+        }
+    }
+
+    let illegal_parameter = |parameter: &str| SimError::IllegalParameter {
+        module: module_name.to_string(),
+        cell: cell_name.to_string(),
+        parameter: parameter.to_string(),
+        value: json_cell
+            .parameters
+            .get(parameter)
+            .map_or_else(|| "".to_string(), |it| it.to_string()),
+    };
+
+    let kind = match kind {
+        Some(kind) => kind,
+        None => match json_cell.parameters.get("FLAVOR").and_then(Value::as_str) {
+            Some("assert") => model::FormalKind::Assert,
+            Some("assume") => model::FormalKind::Assume,
+            Some("cover") => model::FormalKind::Cover,
+            Some("live") => model::FormalKind::Live,
+            Some("fair") => model::FormalKind::Fair,
+            _ => return Err(illegal_parameter("FLAVOR")),
+        },
+    };
+
+    let mut port_trg = None;
+    let mut polarity = Edge::POSITIVE;
+    if let Some(trg_enable) = json_cell.parameters.get("TRG_ENABLE") {
+        match parse_int_parameter(trg_enable) {
+            Some(0) => {}
+            Some(1) => {
+                let conn_trg = connections.get("TRG")?;
+                if conn_trg.wires.len() != 1 {
+                    return Err(illegal_parameter("TRG_WIDTH"));
+                }
+                if conn_trg.polarity == 0 {
+                    polarity = Edge::NEGATIVE;
+                }
+                port_trg = Some(connections.to_in_port(conn_trg));
+            }
+            _ => return Err(illegal_parameter("TRG_ENABLE")),
+        }
+    }
+
+    Ok(model::FormalCell {
+        name: cell_name.to_string(),
+        kind,
+        src: json_cell
+            .attributes
+            .get("src")
+            .and_then(Value::as_str)
+            .map(|it| it.to_string()),
+        port_a: connections.to_in_port(conn_a),
+        port_en: connections.to_in_port(conn_en),
+        port_trg,
+        polarity,
+    })
+}
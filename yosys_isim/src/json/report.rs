@@ -0,0 +1,127 @@
+use crate::json;
+use crate::json::Parameter;
+use crate::json::recognize_parameter;
+use std::collections::BTreeMap;
+use std::fmt;
+
+const MAX_EXAMPLES: usize = 3;
+
+/// Everything in a netlist the simulator does not understand, collected over all modules.
+#[derive(Debug, Clone, Default)]
+pub struct SupportReport {
+    pub unsupported_cell_types: Vec<UnsupportedCellType>,
+    pub unknown_parameters: Vec<UnknownParameter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsupportedCellType {
+    pub cell_type: String,
+    pub count: usize,
+    /// up to three instances as `module.cell`
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnknownParameter {
+    pub cell_type: String,
+    pub parameter: String,
+    pub count: usize,
+    /// up to three instances as `module.cell`
+    pub examples: Vec<String>,
+}
+
+impl SupportReport {
+    pub fn is_empty(&self) -> bool {
+        self.unsupported_cell_types.is_empty() && self.unknown_parameters.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Occurrences {
+    count: usize,
+    examples: Vec<String>,
+}
+
+impl Occurrences {
+    fn add(&mut self, module_name: &str, cell_name: &str) {
+        self.count += 1;
+        self.examples.push(format!("{}.{}", module_name, cell_name));
+    }
+
+    fn into_examples(mut self) -> Vec<String> {
+        self.examples.sort();
+        self.examples.truncate(MAX_EXAMPLES);
+        self.examples
+    }
+}
+
+pub fn check_netlist(json_netlist: &json::Netlist) -> SupportReport {
+    let mut cell_types: BTreeMap<&str, Occurrences> = BTreeMap::new();
+    let mut parameters: BTreeMap<(&str, &str), Occurrences> = BTreeMap::new();
+
+    for (module_name, json_module) in json_netlist.modules.iter() {
+        for (cell_name, json_cell) in json_module.cells.iter() {
+            if let json::CellType::Unsupported(cell_type) = &json_cell.r#type {
+                cell_types
+                    .entry(cell_type)
+                    .or_default()
+                    .add(module_name, cell_name);
+                continue;
+            }
+
+            for parameter in json_cell.parameters.keys() {
+                if let Parameter::Unknown = recognize_parameter(json_cell, parameter) {
+                    parameters
+                        .entry((json_cell.r#type.name(), parameter))
+                        .or_default()
+                        .add(module_name, cell_name);
+                }
+            }
+        }
+    }
+
+    SupportReport {
+        unsupported_cell_types: cell_types
+            .into_iter()
+            .map(|(cell_type, occurrences)| UnsupportedCellType {
+                cell_type: cell_type.to_string(),
+                count: occurrences.count,
+                examples: occurrences.into_examples(),
+            })
+            .collect(),
+        unknown_parameters: parameters
+            .into_iter()
+            .map(|((cell_type, parameter), occurrences)| UnknownParameter {
+                cell_type: cell_type.to_string(),
+                parameter: parameter.to_string(),
+                count: occurrences.count,
+                examples: occurrences.into_examples(),
+            })
+            .collect(),
+    }
+}
+
+impl fmt::Display for SupportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for it in self.unsupported_cell_types.iter() {
+            writeln!(
+                f,
+                "unsupported cell type [{}] x{} (e.g. {})",
+                it.cell_type,
+                it.count,
+                it.examples.join(", ")
+            )?;
+        }
+        for it in self.unknown_parameters.iter() {
+            writeln!(
+                f,
+                "unknown parameter [{}] on [{}] x{} (e.g. {})",
+                it.parameter,
+                it.cell_type,
+                it.count,
+                it.examples.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -4,3 +4,4 @@ pub mod macros;
 pub mod model;
 pub mod ops;
 pub mod sim;
+pub mod verify;
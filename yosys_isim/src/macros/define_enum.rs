@@ -2,7 +2,7 @@
 macro_rules! count {
     () => { 0usize };
     ($head:ident $(, $tail:ident)*) => {
-        1usize + crate::count!($($tail),*)
+        1usize + $crate::count!($($tail),*)
     };
 }
 
@@ -17,7 +17,7 @@ macro_rules! define_enum {
                 $($variants),+
             }
 
-            pub const [<$name _Len>]: usize = crate::count!($($variants),+);
+            pub const [<$name _Len>]: usize = $crate::count!($($variants),+);
 
             pub const [<$name _Variants>]: [$name; [<$name _Len>]] = [$($name::$variants),+];
         }
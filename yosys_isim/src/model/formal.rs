@@ -0,0 +1,56 @@
+use crate::common::HasName;
+use crate::model::CellInPort;
+use crate::sim::Edge;
+use std::fmt;
+
+/// What a [FormalCell] checks, from the type of a `$assert`, `$assume`, `$cover`, `$live`
+/// or `$fair` cell, or from the `FLAVOR` of a `$check` cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormalKind {
+    Assert,
+    Assume,
+    Cover,
+    Live,
+    Fair,
+}
+
+impl FormalKind {
+    /// asserts and assumes fail, the others only count how often they hold
+    pub fn can_fail(&self) -> bool {
+        matches!(self, FormalKind::Assert | FormalKind::Assume)
+    }
+}
+
+impl fmt::Display for FormalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormalKind::Assert => write!(f, "assert"),
+            FormalKind::Assume => write!(f, "assume"),
+            FormalKind::Cover => write!(f, "cover"),
+            FormalKind::Live => write!(f, "live"),
+            FormalKind::Fair => write!(f, "fair"),
+        }
+    }
+}
+
+/// A cell of a design read with `read_verilog -formal`. It drives no wires, so it is kept apart
+/// from the [crate::model::Cell]s and evaluated by the [crate::sim::Sim] after every frame.
+#[derive(Debug, Clone)]
+pub struct FormalCell {
+    pub name: String,
+    pub kind: FormalKind,
+    /// the `src` attribute, like `design.sv:12.5-12.20`
+    pub src: Option<String>,
+    pub port_a: CellInPort,
+    pub port_en: CellInPort,
+    /// only for clocked `$check` cells, which sample `A` and `EN` before the edge of `TRG`
+    pub port_trg: Option<CellInPort>,
+    pub polarity: Edge,
+}
+
+impl HasName for FormalCell {
+    const LABEL: &'static str = "formal cell";
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
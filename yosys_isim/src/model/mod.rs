@@ -6,3 +6,5 @@ pub mod cell;
 pub use cell::*;
 pub mod wires;
 pub use wires::*;
+pub mod formal;
+pub use formal::*;
@@ -1,13 +1,16 @@
-use crate::common::FindByName;
 use crate::common::HasName;
 use crate::common::SimError;
 use crate::common::Vec4;
 use crate::model::Cell;
 use crate::model::Dir;
+use crate::model::FormalCell;
 use crate::model::HWire;
+use crate::model::HWireOrLogic;
 use crate::model::In;
 use crate::model::Out;
 use crate::model::Port;
+use crate::sim::Logic;
+use std::fmt;
 
 pub type ModuleInPort<const L: usize = 0> = Port<In, HWire, L>;
 pub type ModuleOutPort<const L: usize = 0> = Port<Out, HWire, L>;
@@ -18,9 +21,72 @@ pub struct Module {
     pub cells: Vec<Cell>,
     pub in_ports: Vec4<ModuleInPort>,
     pub out_ports: Vec4<ModuleOutPort>,
+    /// all in and out ports, sorted by name
+    pub ports: Vec<PortInfo>,
+    pub nets: Vec<Net>,
+    /// the cells of `read_verilog -formal`, not part of `cells`
+    pub formal_cells: Vec<FormalCell>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortDirection {
+    Input,
+    Output,
+}
+
+impl fmt::Display for PortDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortDirection::Input => write!(f, "input"),
+            PortDirection::Output => write!(f, "output"),
+        }
+    }
+}
+
+/// A port of a [Module] as declared in the netlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortInfo {
+    pub name: String,
+    pub direction: PortDirection,
+    pub width: usize,
+    pub signed: bool,
+}
+
+impl HasName for PortInfo {
+    const LABEL: &'static str = "port";
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A named net of the module, from the `netnames` of the netlist.
+#[derive(Debug, Clone)]
+pub struct Net {
+    pub name: String,
+    pub wires: Vec4<HWireOrLogic>,
+    /// the power-up value from the `init` attribute, if any
+    pub init: Option<Vec4<Logic>>,
+}
+
+impl HasName for Net {
+    const LABEL: &'static str = "net";
+    fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 impl Module {
+    pub fn port_info(&self, name: &str) -> Result<&PortInfo, SimError> {
+        self.ports
+            .iter()
+            .find(|it| it.name == name)
+            .ok_or_else(|| SimError::PortNotFound {
+                module: self.name.to_string(),
+                cell: None,
+                port: name.to_string(),
+            })
+    }
+
     pub fn get_in_port<const L: usize>(&self, name: &str) -> Result<ModuleInPort<L>, SimError> {
         Ok(self
             .get_port_dynamic(&self.in_ports, name, L)?
@@ -41,20 +107,27 @@ impl Module {
         name: &str,
         width: usize,
     ) -> Result<&'a Port<D, W>, SimError> {
-        let port = ports.iter().find_by_name(name)?;
+        let port =
+            ports
+                .iter()
+                .find(|it| it.name.eq(name))
+                .ok_or_else(|| SimError::PortNotFound {
+                    module: self.name.to_string(),
+                    cell: None,
+                    port: name.to_string(),
+                })?;
 
         if port.wires.len() != width {
-            Err(SimError::SimError {
-                msg: format!(
-                    "wrong port width [{}] on [{}],  actual is [{}]",
-                    width,
-                    name,
-                    port.wires.len()
-                ),
-            })?;
+            return Err(SimError::PortWidthMismatch {
+                module: self.name.to_string(),
+                cell: None,
+                port: name.to_string(),
+                expected: width,
+                actual: port.wires.len(),
+            });
         }
 
-        Ok(&port)
+        Ok(port)
     }
 }
 
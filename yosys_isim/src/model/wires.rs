@@ -1,4 +1,3 @@
-use crate::common::SimError;
 use crate::common::Vec4;
 use crate::sim::Logic;
 use smallvec::smallvec;
@@ -12,20 +11,16 @@ pub enum HWireOrLogic {
 }
 
 impl HWireOrLogic {
+    /// returns `None` if any of the wires is a constant Logic value
     #[allow(non_snake_case)]
-    pub fn only_HWires(wires: &[HWireOrLogic]) -> Result<Vec4<HWire>, SimError> {
-        let mut h_wires: Vec4<HWire> = smallvec![ 0usize; wires.len()    ];
-        for i in 0..h_wires.len() {
-            h_wires[i] = match wires[i] {
-                HWireOrLogic::HWire(h_wire) => h_wire,
-                HWireOrLogic::Logic(_) => {
-                    return Err(SimError::IllegalState {
-                        msg: "wires contained constant Logic values, which is unexpected"
-                            .to_string(),
-                    });
-                }
+    pub fn only_HWires(wires: &[HWireOrLogic]) -> Option<Vec4<HWire>> {
+        let mut h_wires: Vec4<HWire> = smallvec![0usize; wires.len()];
+        for (h_wire, wire) in h_wires.iter_mut().zip(wires.iter()) {
+            *h_wire = match wire {
+                HWireOrLogic::HWire(h_wire) => *h_wire,
+                HWireOrLogic::Logic(_) => return None,
             }
         }
-        Ok(h_wires)
+        Some(h_wires)
     }
 }
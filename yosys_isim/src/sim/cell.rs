@@ -10,11 +10,14 @@ use crate::model::HWireOrLogic;
 use crate::model::TernaryOpCell;
 use crate::model::UnaryOpCell;
 use crate::ops::BinaryOp;
-use crate::ops::UnaryOp;
 use crate::sim::Edge;
+use crate::sim::Instr;
+use crate::sim::LaneSimState;
+use crate::sim::Lanes;
 use crate::sim::Logic;
 use crate::sim::SimState;
 use crate::sim::StateRef;
+use crate::sim::TapeBuilder;
 use enum_dispatch::enum_dispatch;
 use smallvec::smallvec;
 
@@ -23,7 +26,14 @@ pub trait CellSimModel {
     fn name(&self) -> &str;
     fn in_ports(&self) -> Vec4<&CellInPort>;
     fn out_ports(&self) -> Vec4<&CellOutPort>;
+    /// sequential cells write their outputs deferred, at the end of a sub-frame
+    fn is_sequential(&self) -> bool {
+        false
+    }
     fn simulate(&self, sim: &mut SimState);
+    fn simulate_lanes(&self, sim: &mut LaneSimState);
+    /// lowers the cell to bit operations in the same order [CellSimModel::simulate] evaluates them
+    fn compile(&self, tape: &mut TapeBuilder);
 }
 
 pub struct CellWires {}
@@ -47,7 +57,7 @@ impl CellWires {
     }
 
     pub fn get_all_h_wires<'a>(cell: &'a Cell) -> impl Iterator<Item = HWire> + 'a {
-        Self::get_in_port_h_wires(&cell).chain(Self::get_out_port_h_wires(&cell))
+        Self::get_in_port_h_wires(cell).chain(Self::get_out_port_h_wires(cell))
     }
 }
 
@@ -67,14 +77,37 @@ impl CellSimModel for UnaryOpCell {
 
         sim.get_wires_or_logic(StateRef::Cur, &self.port_a.wires, &mut a);
 
-        let not = &sim.ops.unary[UnaryOp::NOT];
+        let op = &sim.ops.unary[self.op];
+
+        for i in 0..a.len() {
+            y[i] = op[a[i]];
+        }
+
+        sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
+    }
+    fn simulate_lanes(&self, sim: &mut LaneSimState) {
+        let mut a: Vec4<Lanes> = smallvec![Lanes::X; self.port_a.wires.len()];
+        let mut y: Vec4<Lanes> = smallvec![Lanes::X; self.port_y.wires.len()];
+
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_a.wires, &mut a);
+
+        let op = &sim.ops.unary[self.op];
 
         for i in 0..a.len() {
-            y[i] = not[a[i]];
+            y[i] = op.eval_lanes(a[i]);
         }
 
         sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
     }
+    fn compile(&self, tape: &mut TapeBuilder) {
+        for (a, y) in self.port_a.wires.iter().zip(self.port_y.wires.iter()) {
+            tape.push(Instr::Unary {
+                op: self.op,
+                a: tape.slot(a),
+                y: tape.wire_slot(*y),
+            });
+        }
+    }
 }
 
 impl CellSimModel for BinaryOpCell {
@@ -102,6 +135,31 @@ impl CellSimModel for BinaryOpCell {
 
         sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
     }
+    fn simulate_lanes(&self, sim: &mut LaneSimState) {
+        let mut a: Vec4<Lanes> = smallvec![Lanes::X; self.port_a.wires.len()];
+        let mut b: Vec4<Lanes> = smallvec![Lanes::X; self.port_b.wires.len()];
+        let mut y: Vec4<Lanes> = smallvec![Lanes::X; self.port_y.wires.len()];
+
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_a.wires, &mut a);
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_b.wires, &mut b);
+
+        let op = &sim.ops.binary[self.op];
+        for i in 0..a.len() {
+            y[i] = op.eval_lanes(a[i], b[i]);
+        }
+
+        sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
+    }
+    fn compile(&self, tape: &mut TapeBuilder) {
+        for i in 0..self.port_y.wires.len() {
+            tape.push(Instr::Binary {
+                op: self.op,
+                a: tape.slot(&self.port_a.wires[i]),
+                b: tape.slot(&self.port_b.wires[i]),
+                y: tape.wire_slot(self.port_y.wires[i]),
+            });
+        }
+    }
 }
 
 impl CellSimModel for DFlipFlopCell {
@@ -114,6 +172,9 @@ impl CellSimModel for DFlipFlopCell {
     fn out_ports(&self) -> Vec4<&CellOutPort> {
         Vec4::from_slice(&[&self.port_q])
     }
+    fn is_sequential(&self) -> bool {
+        true
+    }
     fn simulate(&self, sim: &mut SimState) {
         assert_eq!(self.port_clk.wires.len(), 1);
 
@@ -127,11 +188,50 @@ impl CellSimModel for DFlipFlopCell {
                 if clk[0] == self.polarity {
                     let mut d: Vec4<Logic> = smallvec![Logic::X; self.port_d.wires.len()];
                     sim.get_wires_or_logic(StateRef::Cur, &self.port_d.wires, &mut d);
-                    sim.set_wires_deferred(&self.port_q.wires, &mut d);
+                    sim.set_wires_deferred(&self.port_q.wires, &d);
                 }
             }
         }
     }
+    fn simulate_lanes(&self, sim: &mut LaneSimState) {
+        assert_eq!(self.port_clk.wires.len(), 1);
+
+        let mut clk_prev: [Lanes; 1] = [Lanes::X; 1];
+        let mut clk_cur: [Lanes; 1] = [Lanes::X; 1];
+        sim.get_wires_or_logic(StateRef::Prev, &self.port_clk.wires, &mut clk_prev);
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_clk.wires, &mut clk_cur);
+
+        let triggered: u64 = match self.polarity {
+            Edge::POSITIVE => clk_prev[0].mask(Logic::_0) & clk_cur[0].mask(Logic::_1),
+            Edge::NEGATIVE => clk_prev[0].mask(Logic::_1) & clk_cur[0].mask(Logic::_0),
+            Edge::NONE | Edge::X => 0,
+        };
+
+        if triggered != 0 {
+            let mut d: Vec4<Lanes> = smallvec![Lanes::X; self.port_d.wires.len()];
+            let mut q: Vec4<Lanes> = smallvec![Lanes::X; self.port_q.wires.len()];
+            sim.get_wires_or_logic(StateRef::Cur, &self.port_d.wires, &mut d);
+            sim.get_wires(StateRef::Cur, &self.port_q.wires, &mut q);
+
+            for i in 0..q.len() {
+                q[i] = Lanes::select(triggered, d[i], q[i]);
+            }
+            sim.set_wires_deferred(&self.port_q.wires, &q);
+        }
+    }
+    fn compile(&self, tape: &mut TapeBuilder) {
+        assert_eq!(self.port_clk.wires.len(), 1);
+
+        let clk = tape.slot(&self.port_clk.wires[0]);
+        for (d, q) in self.port_d.wires.iter().zip(self.port_q.wires.iter()) {
+            tape.push(Instr::DFlipFlop {
+                polarity: self.polarity,
+                clk,
+                d: tape.slot(d),
+                q: tape.wire_slot(*q),
+            });
+        }
+    }
 }
 
 impl CellSimModel for AddCell {
@@ -167,6 +267,55 @@ impl CellSimModel for AddCell {
 
         sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
     }
+    fn simulate_lanes(&self, sim: &mut LaneSimState) {
+        let mut a: Vec4<Lanes> = smallvec![Lanes::X; self.port_a.wires.len()];
+        let mut b: Vec4<Lanes> = smallvec![Lanes::X; self.port_b.wires.len()];
+        let mut y: Vec4<Lanes> = smallvec![Lanes::X; self.port_y.wires.len()];
+
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_a.wires, &mut a);
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_b.wires, &mut b);
+
+        let xor = &sim.ops.binary[BinaryOp::XOR];
+        let and = &sim.ops.binary[BinaryOp::AND];
+        let or = &sim.ops.binary[BinaryOp::OR];
+
+        let mut c = Lanes::splat(Logic::_0);
+
+        for i in 0..y.len() {
+            let a_xor_b = xor.eval_lanes(a[i], b[i]);
+            y[i] = xor.eval_lanes(a_xor_b, c);
+            c = or.eval_lanes(and.eval_lanes(a_xor_b, c), and.eval_lanes(a[i], b[i]));
+        }
+
+        sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
+    }
+    fn compile(&self, tape: &mut TapeBuilder) {
+        let mut c = tape.const_slot(Logic::_0);
+
+        for i in 0..self.port_y.wires.len() {
+            let a = tape.slot(&self.port_a.wires[i]);
+            let b = tape.slot(&self.port_b.wires[i]);
+            let y = tape.wire_slot(self.port_y.wires[i]);
+
+            let a_xor_b = tape.temp_slot();
+            let a_and_b = tape.temp_slot();
+            let a_xor_b_and_c = tape.temp_slot();
+            let next_c = tape.temp_slot();
+
+            #[rustfmt::skip]
+            let instrs = [
+                Instr::Binary { op: BinaryOp::XOR, a, b, y: a_xor_b },
+                Instr::Binary { op: BinaryOp::XOR, a: a_xor_b, b: c, y },
+                Instr::Binary { op: BinaryOp::AND, a: a_xor_b, b: c, y: a_xor_b_and_c },
+                Instr::Binary { op: BinaryOp::AND, a, b, y: a_and_b },
+                Instr::Binary { op: BinaryOp::OR, a: a_xor_b_and_c, b: a_and_b, y: next_c },
+            ];
+            for instr in instrs {
+                tape.push(instr);
+            }
+            c = next_c;
+        }
+    }
 }
 
 impl CellSimModel for TernaryOpCell {
@@ -197,4 +346,33 @@ impl CellSimModel for TernaryOpCell {
 
         sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
     }
+    fn simulate_lanes(&self, sim: &mut LaneSimState) {
+        let mut a: Vec4<Lanes> = smallvec![Lanes::X; self.port_a.wires.len()];
+        let mut b: Vec4<Lanes> = smallvec![Lanes::X; self.port_b.wires.len()];
+        let mut c: Vec4<Lanes> = smallvec![Lanes::X; self.port_c.wires.len()];
+        let mut y: Vec4<Lanes> = smallvec![Lanes::X; self.port_y.wires.len()];
+
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_a.wires, &mut a);
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_b.wires, &mut b);
+        sim.get_wires_or_logic(StateRef::Cur, &self.port_c.wires, &mut c);
+
+        let op = &sim.ops.ternary[self.op];
+
+        for i in 0..y.len() {
+            y[i] = op.eval_lanes(a[i], b[i], c[i]);
+        }
+
+        sim.set_wires(StateRef::Cur, &self.port_y.wires, &y);
+    }
+    fn compile(&self, tape: &mut TapeBuilder) {
+        for i in 0..self.port_y.wires.len() {
+            tape.push(Instr::Ternary {
+                op: self.op,
+                a: tape.slot(&self.port_a.wires[i]),
+                b: tape.slot(&self.port_b.wires[i]),
+                c: tape.slot(&self.port_c.wires[i]),
+                y: tape.wire_slot(self.port_y.wires[i]),
+            });
+        }
+    }
 }
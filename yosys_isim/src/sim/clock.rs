@@ -0,0 +1,53 @@
+use crate::model::HWire;
+use crate::sim::Logic;
+
+/// A periodic clock, in arbitrary units of time.
+/// It rises at `phase + k * period` and stays high for `duty_cycle` of the period.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Clock {
+    pub period: u64,
+    pub phase: u64,
+    pub duty_cycle: f64,
+}
+
+impl Clock {
+    pub fn new(period: u64) -> Self {
+        Clock {
+            period,
+            phase: 0,
+            duty_cycle: 0.5,
+        }
+    }
+
+    /// the time the clock stays high, at least `1` and at most `period - 1`
+    pub fn high_time(&self) -> u64 {
+        ((self.period as f64 * self.duty_cycle).round() as u64).clamp(1, self.period - 1)
+    }
+
+    /// `0` before the first rising edge
+    pub fn value_at(&self, time: u64) -> Logic {
+        if time < self.phase {
+            return Logic::_0;
+        }
+        ((time - self.phase) % self.period < self.high_time()).into()
+    }
+
+    /// the time of the first edge after `time`
+    pub fn next_edge_after(&self, time: u64) -> u64 {
+        if time < self.phase {
+            return self.phase;
+        }
+        let offset = (time - self.phase) % self.period;
+        let start = time - offset;
+        match offset < self.high_time() {
+            true => start + self.high_time(),
+            false => start + self.period,
+        }
+    }
+}
+
+/// A [Clock] driving an in port.
+pub(super) struct ClockSource {
+    pub clock: Clock,
+    pub h_wire: HWire,
+}
@@ -0,0 +1,103 @@
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Scheduler {
+    /// evaluates every cell on every sub-frame
+    #[default]
+    FullSweep,
+    /// evaluates only the cells whose inputs changed, in levelized order
+    EventDriven,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Engine {
+    /// dispatches to the [crate::sim::CellSimModel] of every cell
+    #[default]
+    Interpreter,
+    /// runs the update order lowered to a flat [crate::sim::Tape] of bit operations,
+    /// the [Scheduler] is ignored
+    Tape,
+    /// compiles the [crate::sim::Tape] to native code with Cranelift,
+    /// the [Scheduler] is ignored. Needs the `jit` feature, otherwise
+    /// [crate::sim::Design::try_with_config] fails with [crate::common::SimError::JitError].
+    Jit,
+}
+
+/// Simulates the weakly connected regions of a module on a thread pool.
+/// Only applies to the [Scheduler::FullSweep] of the [Engine::Interpreter] and to the
+/// [Engine::Tape], otherwise and for modules with less than `min_cells` cells the simulation
+/// stays serial.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Parallel {
+    pub num_threads: usize,
+    pub min_cells: usize,
+}
+
+impl Default for Parallel {
+    fn default() -> Self {
+        Parallel {
+            num_threads: std::thread::available_parallelism().map_or(1, |it| it.get()),
+            min_cells: 4096,
+        }
+    }
+}
+
+/// Keeps a [crate::sim::Snapshot] every `checkpoint_interval` frames and the in ports of every
+/// frame, so [crate::sim::Sim::goto_frame] can replay any of the last `max_frames` frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct History {
+    pub checkpoint_interval: usize,
+    pub max_frames: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            checkpoint_interval: 256,
+            max_frames: 65536,
+        }
+    }
+}
+
+/// How cells evaluate inputs that are `X`, selects the [crate::sim::OpFns] of a simulation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum XPolicy {
+    /// the output is `X` only if it depends on the `X` inputs, e.g. `0 & X = 0`
+    #[default]
+    Precise,
+    /// the output is `X` if any input is `X`
+    Pessimistic,
+    /// `X` inputs are read as `0`, and every wire that would start as `X` starts as `0`,
+    /// or random with [RegisterInit::Random]
+    TwoState,
+    /// `X` inputs are read as `0` or `1`, chosen at random for every cell function and
+    /// combination of inputs, and every wire that would start as `X` starts random.
+    /// The same `seed` gives the same choices.
+    TwoStateRandom { seed: u64 },
+}
+
+/// The power-up value of registers without an `init` attribute.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RegisterInit {
+    #[default]
+    X,
+    Zero,
+    One,
+    /// the same random values for the same seed
+    Random {
+        seed: u64,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SimConfig {
+    pub scheduler: Scheduler,
+    pub engine: Engine,
+    /// serial if `None`
+    pub parallel: Option<Parallel>,
+    /// no reverse stepping if `None`
+    pub history: Option<History>,
+    pub register_init: RegisterInit,
+    pub x_policy: XPolicy,
+    /// halts the loops of frames like [crate::sim::Sim::run_until] right after the frame in
+    /// which an `$assert` or `$assume` cell failed
+    pub stop_on_formal_failure: bool,
+}
@@ -0,0 +1,215 @@
+use crate::common::Random;
+use crate::common::SimError;
+use crate::model::HCell;
+use crate::model::HWireOrLogic;
+use crate::model::Module;
+use crate::sim::CellSimModel;
+use crate::sim::CellWires;
+use crate::sim::Engine;
+#[cfg(feature = "jit")]
+use crate::sim::Jit;
+use crate::sim::Logic;
+use crate::sim::OpFns;
+use crate::sim::RegisterInit;
+use crate::sim::Scheduler;
+use crate::sim::Sim;
+use crate::sim::SimConfig;
+use crate::sim::Tape;
+use crate::sim::WireNode;
+use crate::sim::XPolicy;
+use crate::sim::compute_cell_graph;
+use crate::sim::compute_cell_update_order;
+use crate::sim::compute_num_wires;
+use crate::sim::compute_wire_graph;
+use crate::sim::partition::Partitions;
+use crate::sim::partition::compute_partitions;
+use rayon::ThreadPoolBuilder;
+use static_assertions::assert_impl_all;
+use std::sync::Arc;
+
+/// A [Module] prepared for simulation: its schedule and, depending on the [SimConfig],
+/// its [Tape], compiled code and partitions. None of it changes while simulating,
+/// so a single design can be shared by any number of [Sim]s, also across threads.
+pub struct Design<'m> {
+    pub(super) module: &'m Module,
+    pub(super) config: SimConfig,
+    pub(super) num_wires: usize,
+    pub(super) update_order: Vec<HCell>,
+    pub(super) wire_nodes: Vec<WireNode>,
+    pub(super) cell_ranks: Vec<Option<usize>>,
+    /// the wires after a reset
+    pub(super) initial_wires: Vec<Logic>,
+    pub(super) ops: &'static OpFns,
+    /// only compiled for [Engine::Tape] and [Engine::Jit]
+    pub(super) tape: Option<Tape>,
+    #[cfg(feature = "jit")]
+    pub(super) jit: Option<Jit>,
+    /// only built if [SimConfig::parallel] applies
+    pub(super) partitions: Option<Partitions>,
+}
+
+assert_impl_all!(Design<'static>: Send, Sync);
+
+impl<'m> Design<'m> {
+    pub fn new(module: &'m Module) -> Self {
+        Self::with_config(module, SimConfig::default())
+    }
+
+    /// # Panics
+    ///
+    /// if [Design::try_with_config] fails
+    pub fn with_config(module: &'m Module, config: SimConfig) -> Self {
+        Self::try_with_config(module, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fails if the thread pool of [SimConfig::parallel] cannot be built or if [Engine::Jit]
+    /// is selected and the host is not supported by Cranelift or the `jit` feature is off.
+    pub fn try_with_config(module: &'m Module, config: SimConfig) -> Result<Self, SimError> {
+        let num_wires: usize = compute_num_wires(module);
+
+        let wire_nodes = compute_wire_graph(module, num_wires);
+        let cell_nodes = compute_cell_graph(module, &wire_nodes);
+        let update_order = compute_cell_update_order(module, &cell_nodes);
+        let cell_ranks = compute_cell_ranks(module.cells.len(), &update_order);
+        let initial_wires =
+            compute_initial_wires(module, num_wires, config.register_init, config.x_policy);
+        let ops = OpFns::of(config.x_policy);
+
+        let partitions = match (config.parallel, config.engine, config.scheduler) {
+            (Some(parallel), Engine::Interpreter, Scheduler::FullSweep)
+            | (Some(parallel), Engine::Tape, _)
+                if parallel.num_threads > 1 && module.cells.len() >= parallel.min_cells =>
+            {
+                let partition_update_orders =
+                    compute_partitions(&cell_nodes, &update_order, parallel.num_threads);
+
+                match partition_update_orders.len() > 1 {
+                    true => {
+                        let pool = ThreadPoolBuilder::new()
+                            .num_threads(parallel.num_threads)
+                            .build()
+                            .map_err(|err| SimError::IllegalState {
+                                msg: format!("could not build the thread pool: {}", err),
+                            })?;
+                        Some(Partitions::new(
+                            module,
+                            num_wires,
+                            pool,
+                            partition_update_orders,
+                            config.engine == Engine::Tape,
+                        ))
+                    }
+                    false => None,
+                }
+            }
+            _ => None,
+        };
+
+        let tape = match (config.engine, &partitions) {
+            (Engine::Interpreter, _) | (_, Some(_)) => None,
+            _ => Some(Tape::compile(module, &update_order, num_wires)),
+        };
+
+        #[cfg(feature = "jit")]
+        let jit = match (config.engine, &tape) {
+            (Engine::Jit, Some(tape)) => Some(Jit::compile(tape, ops)?),
+            _ => None,
+        };
+        #[cfg(not(feature = "jit"))]
+        if config.engine == Engine::Jit {
+            return Err(SimError::JitError {
+                msg: "yosys_isim is built without the `jit` feature".to_string(),
+            });
+        }
+
+        Ok(Design {
+            module,
+            config,
+            num_wires,
+            update_order,
+            wire_nodes,
+            cell_ranks,
+            initial_wires,
+            ops,
+            tape,
+            #[cfg(feature = "jit")]
+            jit,
+            partitions,
+        })
+    }
+
+    /// a new [Sim] of this design, all of its wires are `X`
+    pub fn spawn(self: &Arc<Self>) -> Sim<'m> {
+        Sim::from_design(self.clone())
+    }
+
+    pub fn module(&self) -> &'m Module {
+        self.module
+    }
+
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    /// the number of partitions simulated in parallel, `1` if the simulation is serial
+    pub fn num_partitions(&self) -> usize {
+        self.partitions.as_ref().map_or(1, |it| it.len())
+    }
+}
+
+/// the `init` attributes of the nets take precedence over the [RegisterInit]
+pub(super) fn compute_initial_wires(
+    module: &Module,
+    num_wires: usize,
+    register_init: RegisterInit,
+    x_policy: XPolicy,
+) -> Vec<Logic> {
+    let mut wires = vec![Logic::X; num_wires];
+
+    let mut random = Random::new(match (register_init, x_policy) {
+        (RegisterInit::Random { seed }, _) => seed,
+        (_, XPolicy::TwoStateRandom { seed }) => seed,
+        _ => 0,
+    });
+
+    for cell in module.cells.iter().filter(|it| it.is_sequential()) {
+        for h_wire in CellWires::get_out_port_h_wires(cell) {
+            wires[h_wire] = match register_init {
+                RegisterInit::X => Logic::X,
+                RegisterInit::Zero => Logic::_0,
+                RegisterInit::One => Logic::_1,
+                RegisterInit::Random { .. } => random.logic(),
+            };
+        }
+    }
+
+    for net in module.nets.iter() {
+        if let Some(init) = &net.init {
+            for (wire, logic) in net.wires.iter().zip(init.iter()) {
+                if let HWireOrLogic::HWire(h_wire) = wire {
+                    wires[*h_wire] = *logic;
+                }
+            }
+        }
+    }
+
+    if let XPolicy::TwoState | XPolicy::TwoStateRandom { .. } = x_policy {
+        for wire in wires.iter_mut().filter(|it| **it == Logic::X) {
+            *wire = match (register_init, x_policy) {
+                (RegisterInit::Random { .. }, _) => random.logic(),
+                (_, XPolicy::TwoStateRandom { .. }) => random.logic(),
+                _ => Logic::_0,
+            };
+        }
+    }
+
+    wires
+}
+
+fn compute_cell_ranks(num_cells: usize, update_order: &[HCell]) -> Vec<Option<usize>> {
+    let mut cell_ranks: Vec<Option<usize>> = vec![None; num_cells];
+    for (rank, h_cell) in update_order.iter().enumerate() {
+        cell_ranks[*h_cell] = Some(rank);
+    }
+    cell_ranks
+}
@@ -1,9 +1,12 @@
+use crate::common::Random;
 use crate::common::Vec4;
 use crate::ops::BinaryOp;
 use crate::ops::BinaryOp_Len;
 use crate::ops::BinaryOp_Variants;
+use crate::sim::Lanes;
 use crate::sim::Logic;
 use crate::sim::Logic_Variants;
+use crate::sim::XPolicy;
 use std::ops::Index;
 
 #[derive(Copy, Clone)]
@@ -28,6 +31,19 @@ impl Index<(Logic, Logic)> for BinaryOpFn {
 }
 
 impl BinaryOpFn {
+    /// evaluates the truth table on every lane, each lane is the union of all
+    /// input combinations that match it
+    pub fn eval_lanes(&self, a: Lanes, b: Lanes) -> Lanes {
+        let mut y = Lanes::default();
+        for a_logic in Logic_Variants {
+            let a_mask = a.mask(a_logic);
+            for b_logic in Logic_Variants {
+                y.or_mask(a_mask & b.mask(b_logic), self[(a_logic, b_logic)]);
+            }
+        }
+        y
+    }
+
     pub(super) fn compile_all() -> [BinaryOpFn; BinaryOp_Len] {
         let mut fs: [BinaryOpFn; BinaryOp_Len] = [BinaryOpFn {
             table: [[Logic::X; 3]; 3],
@@ -40,6 +56,25 @@ impl BinaryOpFn {
         fs
     }
 
+    /// the table of this precise function under `x_policy`
+    pub(super) fn with_x_policy(&self, x_policy: XPolicy, random: &mut Random) -> BinaryOpFn {
+        let mut f = *self;
+        for a in Logic_Variants.into_iter() {
+            for b in Logic_Variants.into_iter() {
+                f.table[a as usize][b as usize] = match x_policy {
+                    XPolicy::Precise => self[(a, b)],
+                    XPolicy::Pessimistic if a == Logic::X || b == Logic::X => Logic::X,
+                    XPolicy::Pessimistic => self[(a, b)],
+                    XPolicy::TwoState => self[(a.to_two_state(), b.to_two_state())],
+                    XPolicy::TwoStateRandom { .. } => {
+                        self[(a.to_random_two_state(random), b.to_random_two_state(random))]
+                    }
+                };
+            }
+        }
+        f
+    }
+
     fn compile(op: BinaryOp) -> BinaryOpFn {
         let mut f = BinaryOpFn {
             table: [[Logic::X; 3]; 3],
@@ -2,11 +2,15 @@ pub mod binary;
 pub mod ternary;
 pub mod unary;
 
+use crate::common::Random;
 use crate::ops::BinaryOp_Len;
 use crate::ops::TernaryOp_Len;
 use crate::ops::UnaryOp_Len;
+use crate::sim::XPolicy;
 pub use binary::*;
 use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
 pub use ternary::*;
 pub use unary::*;
 
@@ -19,6 +23,10 @@ lazy_static! {
         binary: *BINARY_OP_FNS,
         ternary: *TERNARY_OP_FNS,
     };
+    pub static ref PESSIMISTIC_OP_FNS: OpFns = OP_FNS.with_x_policy(XPolicy::Pessimistic);
+    pub static ref TWO_STATE_OP_FNS: OpFns = OP_FNS.with_x_policy(XPolicy::TwoState);
+    /// the tables of [XPolicy::TwoStateRandom] by seed, built once and kept for the whole run
+    static ref TWO_STATE_RANDOM_OP_FNS: Mutex<HashMap<u64, &'static OpFns>> = Mutex::default();
 }
 
 #[derive(Clone)]
@@ -27,3 +35,35 @@ pub struct OpFns {
     pub binary: [BinaryOpFn; BinaryOp_Len],
     pub ternary: [TernaryFn; TernaryOp_Len],
 }
+
+impl OpFns {
+    /// the tables implementing `x_policy`
+    pub fn of(x_policy: XPolicy) -> &'static OpFns {
+        match x_policy {
+            XPolicy::Precise => &OP_FNS,
+            XPolicy::Pessimistic => &PESSIMISTIC_OP_FNS,
+            XPolicy::TwoState => &TWO_STATE_OP_FNS,
+            XPolicy::TwoStateRandom { seed } => TWO_STATE_RANDOM_OP_FNS
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .entry(seed)
+                .or_insert_with(|| Box::leak(Box::new(OP_FNS.with_x_policy(x_policy)))),
+        }
+    }
+
+    fn with_x_policy(&self, x_policy: XPolicy) -> OpFns {
+        let mut random = Random::new(match x_policy {
+            XPolicy::TwoStateRandom { seed } => seed,
+            _ => 0,
+        });
+        OpFns {
+            unary: self.unary.map(|it| it.with_x_policy(x_policy, &mut random)),
+            binary: self
+                .binary
+                .map(|it| it.with_x_policy(x_policy, &mut random)),
+            ternary: self
+                .ternary
+                .map(|it| it.with_x_policy(x_policy, &mut random)),
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use crate::common::Random;
 use crate::ops::TernaryOp;
 use crate::ops::TernaryOp_Len;
 use crate::ops::TernaryOp_Variants;
+use crate::sim::Lanes;
 use crate::sim::Logic;
 use crate::sim::Logic_Variants;
+use crate::sim::XPolicy;
 use std::ops::Index;
 
 #[derive(Copy, Clone)]
@@ -27,6 +30,25 @@ impl Index<(Logic, Logic, Logic)> for TernaryFn {
 }
 
 impl TernaryFn {
+    /// evaluates the truth table on every lane, each lane is the union of all
+    /// input combinations that match it
+    pub fn eval_lanes(&self, a: Lanes, b: Lanes, c: Lanes) -> Lanes {
+        let mut y = Lanes::default();
+        for a_logic in Logic_Variants {
+            let a_mask = a.mask(a_logic);
+            for b_logic in Logic_Variants {
+                let a_b_mask = a_mask & b.mask(b_logic);
+                for c_logic in Logic_Variants {
+                    y.or_mask(
+                        a_b_mask & c.mask(c_logic),
+                        self[(a_logic, b_logic, c_logic)],
+                    );
+                }
+            }
+        }
+        y
+    }
+
     pub(super) fn compile_all() -> [TernaryFn; TernaryOp_Len] {
         let mut fs: [TernaryFn; TernaryOp_Len] = [TernaryFn {
             table: [[[Logic::X; 3]; 3]; 3],
@@ -39,6 +61,33 @@ impl TernaryFn {
         fs
     }
 
+    /// the table of this precise function under `x_policy`
+    pub(super) fn with_x_policy(&self, x_policy: XPolicy, random: &mut Random) -> TernaryFn {
+        let mut f = *self;
+        for a in Logic_Variants.into_iter() {
+            for b in Logic_Variants.into_iter() {
+                for c in Logic_Variants.into_iter() {
+                    f.table[a as usize][b as usize][c as usize] = match x_policy {
+                        XPolicy::Precise => self[(a, b, c)],
+                        XPolicy::Pessimistic if [a, b, c].contains(&Logic::X) => Logic::X,
+                        XPolicy::Pessimistic => self[(a, b, c)],
+                        XPolicy::TwoState => {
+                            self[(a.to_two_state(), b.to_two_state(), c.to_two_state())]
+                        }
+                        XPolicy::TwoStateRandom { .. } => {
+                            self[(
+                                a.to_random_two_state(random),
+                                b.to_random_two_state(random),
+                                c.to_random_two_state(random),
+                            )]
+                        }
+                    };
+                }
+            }
+        }
+        f
+    }
+
     fn compile(op: TernaryOp) -> TernaryFn {
         let mut f = TernaryFn {
             table: [[[Logic::X; 3]; 3]; 3],
@@ -1,9 +1,12 @@
+use crate::common::Random;
 use crate::common::Vec4;
 use crate::ops::UnaryOp;
 use crate::ops::UnaryOp_Len;
 use crate::ops::UnaryOp_Variants;
+use crate::sim::Lanes;
 use crate::sim::Logic;
 use crate::sim::Logic_Variants;
+use crate::sim::XPolicy;
 use std::ops::Index;
 
 #[derive(Copy, Clone)]
@@ -28,6 +31,16 @@ impl Index<Logic> for UnaryOpFn {
 }
 
 impl UnaryOpFn {
+    /// evaluates the truth table on every lane, each lane is the union of all
+    /// input combinations that match it
+    pub fn eval_lanes(&self, a: Lanes) -> Lanes {
+        let mut y = Lanes::default();
+        for a_logic in Logic_Variants {
+            y.or_mask(a.mask(a_logic), self[a_logic]);
+        }
+        y
+    }
+
     pub(super) fn compile_all() -> [UnaryOpFn; UnaryOp_Len] {
         let mut fs: [UnaryOpFn; UnaryOp_Len] = [UnaryOpFn {
             table: [Logic::X; 3],
@@ -40,6 +53,21 @@ impl UnaryOpFn {
         fs
     }
 
+    /// the table of this precise function under `x_policy`
+    pub(super) fn with_x_policy(&self, x_policy: XPolicy, random: &mut Random) -> UnaryOpFn {
+        let mut f = *self;
+        for a in Logic_Variants.into_iter() {
+            f.table[a as usize] = match x_policy {
+                XPolicy::Precise => self[a],
+                XPolicy::Pessimistic if a == Logic::X => Logic::X,
+                XPolicy::Pessimistic => self[a],
+                XPolicy::TwoState => self[a.to_two_state()],
+                XPolicy::TwoStateRandom { .. } => self[a.to_random_two_state(random)],
+            };
+        }
+        f
+    }
+
     fn compile(op: UnaryOp) -> UnaryOpFn {
         let mut f = UnaryOpFn {
             table: [Logic::X; 3],
@@ -0,0 +1,142 @@
+use crate::model::CellInPort;
+use crate::model::FormalCell;
+use crate::model::FormalKind;
+use crate::sim::Edge;
+use crate::sim::Logic;
+use crate::sim::SimState;
+use crate::sim::StateRef;
+use std::fmt;
+
+/// A failed `$assert` or `$assume` cell. See [crate::sim::Sim::formal_failures].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormalEvent {
+    /// the frame after which the cell failed, like [crate::sim::Sim::frame]
+    pub frame: usize,
+    pub time: u64,
+    pub cell: String,
+    pub kind: FormalKind,
+    /// the `src` attribute of the cell, if any
+    pub src: Option<String>,
+}
+
+impl fmt::Display for FormalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] failed in frame {} at time {}",
+            self.kind, self.cell, self.frame, self.time
+        )?;
+        if let Some(src) = &self.src {
+            write!(f, " ({})", src)?;
+        }
+        Ok(())
+    }
+}
+
+/// `A`, `EN` and `TRG` of a formal cell after the last frame.
+#[derive(Clone, Copy)]
+struct Sample {
+    a: Logic,
+    en: Logic,
+    trg: Logic,
+}
+
+/// The formal cells of a [crate::sim::Sim], indexed like [crate::model::Module::formal_cells].
+pub(super) struct FormalState {
+    samples: Vec<Sample>,
+    /// how often each cell failed, for asserts and assumes, or held, for the others
+    pub counts: Vec<usize>,
+    pub failures: Vec<FormalEvent>,
+    /// the number of `failures` of the last frame
+    pub num_new_failures: usize,
+}
+
+impl FormalState {
+    pub fn new(num_cells: usize) -> Self {
+        FormalState {
+            samples: vec![
+                Sample {
+                    a: Logic::X,
+                    en: Logic::X,
+                    trg: Logic::X,
+                };
+                num_cells
+            ],
+            counts: vec![0; num_cells],
+            failures: Vec::new(),
+            num_new_failures: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.failures.clear();
+        self.num_new_failures = 0;
+    }
+
+    /// takes the current values of the cells, without evaluating them
+    pub fn update(&mut self, cells: &[FormalCell], sim_state: &SimState) {
+        self.num_new_failures = 0;
+        for (sample, cell) in self.samples.iter_mut().zip(cells) {
+            *sample = Sample::of(cell, sim_state);
+        }
+    }
+
+    /// Evaluates the cells after a frame. An immediate cell is enabled if `EN` is `1` and holds
+    /// if `A` is `1`, so an assert fails on `A = X`. A clocked cell is evaluated only on the
+    /// edge of `TRG`, with the values of `A` and `EN` before the edge.
+    pub fn check(&mut self, cells: &[FormalCell], frame: usize, time: u64, sim_state: &SimState) {
+        self.num_new_failures = 0;
+        for (i, cell) in cells.iter().enumerate() {
+            let sample = Sample::of(cell, sim_state);
+            let prev_sample = std::mem::replace(&mut self.samples[i], sample);
+
+            let (a, en) = match &cell.port_trg {
+                None => (sample.a, sample.en),
+                Some(_) if Edge::of(prev_sample.trg, sample.trg) == cell.polarity => {
+                    (prev_sample.a, prev_sample.en)
+                }
+                Some(_) => continue,
+            };
+            if en != Logic::_1 {
+                continue;
+            }
+
+            let holds = a == Logic::_1;
+            if cell.kind.can_fail() {
+                if !holds {
+                    self.counts[i] += 1;
+                    self.failures.push(FormalEvent {
+                        frame,
+                        time,
+                        cell: cell.name.clone(),
+                        kind: cell.kind,
+                        src: cell.src.clone(),
+                    });
+                    self.num_new_failures += 1;
+                }
+            } else if holds {
+                self.counts[i] += 1;
+            }
+        }
+    }
+}
+
+impl Sample {
+    fn of(cell: &FormalCell, sim_state: &SimState) -> Self {
+        Sample {
+            a: get_logic(&cell.port_a, sim_state),
+            en: get_logic(&cell.port_en, sim_state),
+            trg: cell
+                .port_trg
+                .as_ref()
+                .map_or(Logic::X, |it| get_logic(it, sim_state)),
+        }
+    }
+}
+
+fn get_logic(port: &CellInPort, sim_state: &SimState) -> Logic {
+    let mut logics = [Logic::X];
+    sim_state.get_wires_or_logic(StateRef::Cur, &port.wires, &mut logics);
+    logics[0]
+}
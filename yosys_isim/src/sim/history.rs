@@ -0,0 +1,78 @@
+use crate::model::HWire;
+use crate::sim::History;
+use crate::sim::Logic;
+use crate::sim::Snapshot;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+/// The checkpoints and in ports recorded for [History].
+/// The oldest checkpoint is always at `first_frame`, the frame of the first inputs.
+pub(super) struct HistoryLog {
+    config: History,
+    pub h_in_wires: Vec<HWire>,
+    checkpoints: VecDeque<Snapshot>,
+    first_frame: usize,
+    /// the time and the in ports when simulating the frame `first_frame + index`
+    inputs: VecDeque<(u64, Vec<Logic>)>,
+}
+
+impl HistoryLog {
+    pub fn new(config: History, h_in_wires: Vec<HWire>, checkpoint: Snapshot) -> Self {
+        HistoryLog {
+            config,
+            h_in_wires,
+            first_frame: checkpoint.frame,
+            checkpoints: VecDeque::from([checkpoint]),
+            inputs: VecDeque::new(),
+        }
+    }
+
+    /// the frames that can be reconstructed
+    pub fn frames(&self) -> RangeInclusive<usize> {
+        self.first_frame..=self.first_frame + self.inputs.len()
+    }
+
+    /// records the inputs of `frame`, which forgets the frames after it
+    pub fn record(&mut self, frame: usize, time: u64, inputs: Vec<Logic>) {
+        self.inputs.truncate(frame - self.first_frame);
+        while self.checkpoints.back().is_some_and(|it| it.frame > frame) {
+            self.checkpoints.pop_back();
+        }
+        self.inputs.push_back((time, inputs));
+    }
+
+    pub fn is_checkpoint(&self, frame: usize) -> bool {
+        frame.is_multiple_of(self.config.checkpoint_interval)
+    }
+
+    /// adds the checkpoint and forgets everything that is older than `max_frames`
+    pub fn checkpoint(&mut self, checkpoint: Snapshot) {
+        let frame = checkpoint.frame;
+        if self.checkpoints.back().is_some_and(|it| it.frame < frame) {
+            self.checkpoints.push_back(checkpoint);
+        }
+
+        let oldest_frame = frame.saturating_sub(self.config.max_frames);
+        while self.checkpoints.len() > 1 && self.checkpoints[1].frame <= oldest_frame {
+            self.checkpoints.pop_front();
+        }
+
+        let first_frame = self.checkpoints[0].frame;
+        self.inputs.drain(..first_frame - self.first_frame);
+        self.first_frame = first_frame;
+    }
+
+    /// the latest checkpoint not after `frame`
+    pub fn nearest_checkpoint(&self, frame: usize) -> &Snapshot {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|it| it.frame <= frame)
+            .unwrap()
+    }
+
+    pub fn inputs(&self, frame: usize) -> (u64, &[Logic]) {
+        let (time, inputs) = &self.inputs[frame - self.first_frame];
+        (*time, inputs)
+    }
+}
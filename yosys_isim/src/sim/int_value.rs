@@ -0,0 +1,96 @@
+use crate::sim::Logic;
+
+/// The value of a port read as an integer, which is only known if none of its bits is `X`.
+/// Big integers are 64 bit words, LSB first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntValue<T> {
+    Known(T),
+    /// the `X` bits are set in `x_mask` and `0` in `value`
+    Unknown {
+        value: T,
+        x_mask: T,
+    },
+}
+
+impl<T> IntValue<T> {
+    pub fn is_known(&self) -> bool {
+        matches!(self, IntValue::Known(_))
+    }
+
+    pub fn known(self) -> Option<T> {
+        match self {
+            IntValue::Known(value) => Some(value),
+            IntValue::Unknown { .. } => None,
+        }
+    }
+
+    /// the value with the `X` bits at `0`
+    pub fn value(&self) -> &T {
+        match self {
+            IntValue::Known(value) | IntValue::Unknown { value, .. } => value,
+        }
+    }
+
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> IntValue<U> {
+        match self {
+            IntValue::Known(value) => IntValue::Known(f(value)),
+            IntValue::Unknown { value, x_mask } => IntValue::Unknown {
+                value: f(value),
+                x_mask: f(x_mask),
+            },
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> IntValue<T> {
+    /// # Panics
+    ///
+    /// if any bit is `X`
+    pub fn unwrap(self) -> T {
+        match self {
+            IntValue::Known(value) => value,
+            IntValue::Unknown { value, x_mask } => {
+                panic!("value {:?} has X bits {:?}", value, x_mask)
+            }
+        }
+    }
+}
+
+/// at least one word
+pub(super) fn logics_to_words(logics: &[Logic]) -> IntValue<Vec<u64>> {
+    let num_words = usize::max(1, logics.len().div_ceil(64));
+    let mut value = vec![0u64; num_words];
+    let mut x_mask = vec![0u64; num_words];
+    for (i, logic) in logics.iter().enumerate() {
+        match logic {
+            Logic::_0 => {}
+            Logic::_1 => value[i / 64] |= 1 << (i % 64),
+            Logic::X => x_mask[i / 64] |= 1 << (i % 64),
+        }
+    }
+
+    match x_mask.iter().all(|it| *it == 0) {
+        true => IntValue::Known(value),
+        false => IntValue::Unknown { value, x_mask },
+    }
+}
+
+/// truncated or extended to `width` bits, with the sign if `signed`
+pub(super) fn words_to_logics(words: &[u64], signed: bool, width: usize) -> Vec<Logic> {
+    let sign = signed && words.last().is_some_and(|it| (*it as i64) < 0);
+    (0..width)
+        .map(|i| match words.get(i / 64) {
+            Some(word) => Logic::from((word >> (i % 64)) & 1),
+            None => Logic::from(sign),
+        })
+        .collect()
+}
+
+/// the lower `width` bits of `word` sign extended
+pub(super) fn sign_extend(word: u64, width: usize) -> i64 {
+    match width {
+        0 => 0,
+        64.. => word as i64,
+        _ => ((word << (64 - width)) as i64) >> (64 - width),
+    }
+}
@@ -0,0 +1,685 @@
+use crate::common::SimError;
+use crate::model::HWire;
+use crate::sim::Edge;
+use crate::sim::Instr;
+use crate::sim::Logic;
+use crate::sim::Logic_Variants;
+use crate::sim::OpFns;
+use crate::sim::SimState;
+use crate::sim::Slot;
+use crate::sim::Tape;
+use cranelift_codegen::ir::AbiParam;
+use cranelift_codegen::ir::FuncRef;
+use cranelift_codegen::ir::InstBuilder;
+use cranelift_codegen::ir::MemFlags;
+use cranelift_codegen::ir::StackSlotData;
+use cranelift_codegen::ir::StackSlotKind;
+use cranelift_codegen::ir::Type;
+use cranelift_codegen::ir::Value;
+use cranelift_codegen::ir::types;
+use cranelift_codegen::settings;
+use cranelift_codegen::settings::Configurable;
+use cranelift_frontend::FunctionBuilder;
+use cranelift_frontend::FunctionBuilderContext;
+use cranelift_jit::JITBuilder;
+use cranelift_jit::JITModule;
+use cranelift_module::FuncId;
+use cranelift_module::Linkage;
+use cranelift_module::Module as CraneliftModule;
+use cranelift_module::default_libcall_names;
+use smallvec::SmallVec;
+use std::collections::HashMap;
+
+/// the tape is split into functions of this many instructions to keep compile times linear
+const MAX_INSTRS_PER_FN: usize = 4096;
+
+/// runs of this many equal operations on consecutive slots are evaluated as one vector
+const VECTOR_LANES: usize = 16;
+const VECTOR: Type = types::I8X16;
+const SCALAR: Type = types::I32;
+
+type JitFn = unsafe extern "C" fn(*const Logic, *mut Logic, *mut Vec<(HWire, Logic)>);
+
+/// A [Tape] compiled to native code with Cranelift.
+///
+/// Every slot is evaluated as two bit planes, whether it may be `0` and whether it may be `1`.
+/// They are the `value` and `unknown` planes of [crate::sim::Lanes] rearranged, so that a truth
+/// table which resolves `X` like [OpFns] does becomes a minimized sum of products over its
+/// boolean inputs. Other truth tables are lowered to the union of their minterms.
+/// Runs of equal operations on consecutive slots, like the bits of a `$and`, share one vector.
+pub struct Jit {
+    module: Option<JITModule>,
+    fns: Vec<JitFn>,
+    /// the outputs of each group of flip-flops, referenced by the compiled code
+    #[allow(clippy::vec_box)]
+    _qs: Vec<Box<[u32]>>,
+    num_slots: usize,
+}
+
+// SAFETY: the module is only touched by `compile` and `drop`, which own the [Jit].
+// The compiled functions never write to their own memory or to `_qs`, every call
+// works on the [SimState] passed to [Jit::run], so they can run on any number of threads.
+unsafe impl Send for Jit {}
+unsafe impl Sync for Jit {}
+
+/// the compiled code stays in the same basic block for all bits of a flip-flop,
+/// a single call appends all of them
+extern "C" fn push_deferred(
+    set_wires_deferred: *mut Vec<(HWire, Logic)>,
+    qs: *const u32,
+    ds: *const u8,
+    len: usize,
+) {
+    let set_wires_deferred = unsafe { &mut *set_wires_deferred };
+    let (qs, ds) = unsafe {
+        (
+            std::slice::from_raw_parts(qs, len),
+            std::slice::from_raw_parts(ds, len),
+        )
+    };
+    for (q, d) in qs.iter().zip(ds.iter()) {
+        set_wires_deferred.push((*q as HWire, Logic_Variants[*d as usize]));
+    }
+}
+
+fn jit_error(err: impl ToString) -> SimError {
+    SimError::JitError {
+        msg: err.to_string(),
+    }
+}
+
+impl Jit {
+    pub fn compile(tape: &Tape, ops: &OpFns) -> Result<Jit, SimError> {
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").map_err(jit_error)?;
+        let isa = cranelift_native::builder()
+            .map_err(jit_error)?
+            .finish(settings::Flags::new(flags))
+            .map_err(jit_error)?;
+
+        let mut jit_builder = JITBuilder::with_isa(isa, default_libcall_names());
+        jit_builder.symbol("push_deferred", push_deferred as *const u8);
+        let mut module = JITModule::new(jit_builder);
+
+        let ptr = module.target_config().pointer_type();
+
+        let mut push_deferred_sig = module.make_signature();
+        for _ in 0..4 {
+            push_deferred_sig.params.push(AbiParam::new(ptr));
+        }
+        let push_deferred_id = module
+            .declare_function("push_deferred", Linkage::Import, &push_deferred_sig)
+            .map_err(jit_error)?;
+
+        let mut ctx = module.make_context();
+        let mut fn_builder_ctx = FunctionBuilderContext::new();
+        let mut func_ids: Vec<FuncId> = Vec::new();
+        let mut qs: Vec<Box<[u32]>> = Vec::new();
+
+        for (index, instrs) in tape.instrs.chunks(MAX_INSTRS_PER_FN).enumerate() {
+            for _ in 0..3 {
+                ctx.func.signature.params.push(AbiParam::new(ptr));
+            }
+
+            let func_id = module
+                .declare_function(
+                    &format!("run_{}", index),
+                    Linkage::Local,
+                    &ctx.func.signature,
+                )
+                .map_err(jit_error)?;
+
+            let mut builder = FunctionBuilder::new(&mut ctx.func, &mut fn_builder_ctx);
+            let push_deferred = module.declare_func_in_func(push_deferred_id, builder.func);
+
+            let entry = builder.create_block();
+            builder.append_block_params_for_function_params(entry);
+            builder.switch_to_block(entry);
+
+            let params = builder.block_params(entry).to_vec();
+            let mut emitter = Emitter {
+                builder,
+                ops,
+                num_wires: tape.num_wires,
+                prev: params[0],
+                cur: params[1],
+                set_wires_deferred: params[2],
+                push_deferred,
+                ptr,
+                qs: &mut qs,
+                cur_planes: HashMap::new(),
+            };
+            let mut instrs: &[Instr] = instrs;
+            while !instrs.is_empty() {
+                instrs = emitter.emit(instrs);
+            }
+
+            let mut builder = emitter.builder;
+            builder.ins().return_(&[]);
+            builder.seal_all_blocks();
+            builder.finalize();
+
+            module
+                .define_function(func_id, &mut ctx)
+                .map_err(jit_error)?;
+            module.clear_context(&mut ctx);
+            func_ids.push(func_id);
+        }
+
+        module.finalize_definitions().map_err(jit_error)?;
+
+        let fns: Vec<JitFn> = func_ids
+            .into_iter()
+            .map(|func_id| unsafe {
+                std::mem::transmute::<*const u8, JitFn>(module.get_finalized_function(func_id))
+            })
+            .collect();
+
+        Ok(Jit {
+            module: Some(module),
+            fns,
+            _qs: qs,
+            num_slots: tape.num_slots,
+        })
+    }
+
+    pub fn run(&self, sim: &mut SimState) {
+        let [prev, cur] = &mut sim.wires;
+
+        // the compiled code accesses the slots without bounds checks
+        assert!(prev.len() >= self.num_slots && cur.len() >= self.num_slots);
+
+        for f in self.fns.iter() {
+            unsafe { f(prev.as_ptr(), cur.as_mut_ptr(), &mut sim.set_wires_deferred) };
+        }
+    }
+}
+
+impl Drop for Jit {
+    fn drop(&mut self) {
+        self.fns.clear();
+        if let Some(module) = self.module.take() {
+            // no function pointer into the module outlives it
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+/// a [Logic] as two planes, each either `0` or `1`, that are never both `0`.
+/// The planes are [SCALAR] for a single slot or [VECTOR] for consecutive slots.
+#[derive(Copy, Clone)]
+struct Planes {
+    may_be_0: Value,
+    may_be_1: Value,
+}
+
+/// a product of inputs, `Some(bool)` if the input has to be that value, `None` if it does not
+/// matter
+type Cube = SmallVec<[Option<bool>; 3]>;
+
+struct Emitter<'a, 'f> {
+    builder: FunctionBuilder<'f>,
+    ops: &'a OpFns,
+    num_wires: usize,
+    prev: Value,
+    cur: Value,
+    set_wires_deferred: Value,
+    push_deferred: FuncRef,
+    ptr: types::Type,
+    qs: &'a mut Vec<Box<[u32]>>,
+    /// the slots already loaded or computed by this function
+    cur_planes: HashMap<Slot, Planes>,
+}
+
+impl Emitter<'_, '_> {
+    /// emits the first instruction, a vector of the first instructions, or all flip-flops
+    /// sharing the first clock, and returns the rest
+    fn emit<'i>(&mut self, instrs: &'i [Instr]) -> &'i [Instr] {
+        if let Instr::DFlipFlop { polarity, clk, .. } = instrs[0] {
+            let len = instrs
+                .iter()
+                .take_while(|it| {
+                    matches!(it, Instr::DFlipFlop { polarity: p, clk: c, .. } if *p == polarity && *c == clk)
+                })
+                .count();
+            self.emit_flip_flops(polarity, clk, &instrs[..len]);
+            return &instrs[len..];
+        }
+
+        let (inputs, y) = operands(&instrs[0]).unwrap();
+        if self.is_vector(instrs) {
+            let inputs: SmallVec<[Planes; 3]> = inputs
+                .iter()
+                .map(|slot| self.load_from(self.cur, *slot, VECTOR))
+                .collect();
+            let planes = self.eval_op(&instrs[0], VECTOR, &inputs);
+            self.store_vector(y, planes);
+            return &instrs[VECTOR_LANES..];
+        }
+
+        let inputs: SmallVec<[Planes; 3]> = inputs.iter().map(|slot| self.load(*slot)).collect();
+        let planes = self.eval_op(&instrs[0], SCALAR, &inputs);
+        self.store(y, planes);
+        &instrs[1..]
+    }
+
+    /// whether the first [VECTOR_LANES] instructions are the same operation on consecutive slots
+    fn is_vector(&self, instrs: &[Instr]) -> bool {
+        let Some((inputs, y)) = operands(&instrs[0]) else {
+            return false;
+        };
+        if instrs.len() < VECTOR_LANES || inputs.iter().any(|it| self.is_const(*it).is_some()) {
+            return false;
+        }
+
+        // the outputs must not be read by a later lane
+        let lanes = VECTOR_LANES as Slot;
+        if inputs.iter().any(|it| *it < y + lanes && y < *it + lanes) {
+            return false;
+        }
+
+        (1..VECTOR_LANES).all(|lane| {
+            let lane_inputs: SmallVec<[Slot; 3]> =
+                inputs.iter().map(|it| *it + lane as Slot).collect();
+            is_same_op(&instrs[0], &instrs[lane])
+                && operands(&instrs[lane]) == Some((lane_inputs, y + lane as Slot))
+        })
+    }
+
+    fn eval_op(&mut self, instr: &Instr, ty: Type, inputs: &[Planes]) -> Planes {
+        match *instr {
+            Instr::Unary { op, .. } => {
+                let table = &self.ops.unary[op];
+                self.eval(ty, inputs, |logics| table[logics[0]])
+            }
+            Instr::Binary { op, .. } => {
+                let table = &self.ops.binary[op];
+                self.eval(ty, inputs, |logics| table[(logics[0], logics[1])])
+            }
+            Instr::Ternary { op, .. } => {
+                let table = &self.ops.ternary[op];
+                self.eval(ty, inputs, |logics| {
+                    table[(logics[0], logics[1], logics[2])]
+                })
+            }
+            Instr::DFlipFlop { .. } => unreachable!(),
+        }
+    }
+
+    fn emit_flip_flops(&mut self, polarity: Edge, clk: Slot, instrs: &[Instr]) {
+        let (from, to) = match polarity {
+            Edge::POSITIVE => (Logic::_0, Logic::_1),
+            Edge::NEGATIVE => (Logic::_1, Logic::_0),
+            Edge::NONE | Edge::X => return,
+        };
+
+        let clk_prev = self.load_prev(clk);
+        let clk_cur = self.load(clk);
+        let from = self.is(clk_prev, from);
+        let to = self.is(clk_cur, to);
+        let triggered = self.builder.ins().band(from, to);
+
+        // values loaded in the push block would not dominate the following instructions
+        let ds_planes: Vec<(Planes, u32)> = instrs
+            .iter()
+            .filter_map(|instr| match *instr {
+                Instr::DFlipFlop { d, q, .. } => Some((d, q)),
+                _ => None,
+            })
+            .map(|(d, q)| (self.load(d), q))
+            .collect();
+
+        let push_block = self.builder.create_block();
+        let next_block = self.builder.create_block();
+        self.builder
+            .ins()
+            .brif(triggered, push_block, &[], next_block, &[]);
+
+        self.builder.switch_to_block(push_block);
+        let ds = self.builder.create_sized_stack_slot(StackSlotData::new(
+            StackSlotKind::ExplicitSlot,
+            instrs.len() as u32,
+            0,
+        ));
+        for (offset, (d, _)) in ds_planes.iter().enumerate() {
+            let d = self.encode_logic(*d);
+            let d = self.builder.ins().ireduce(types::I8, d);
+            self.builder.ins().stack_store(d, ds, offset as i32);
+        }
+        let qs: Box<[u32]> = ds_planes.iter().map(|(_, q)| *q).collect();
+
+        let args = [
+            self.set_wires_deferred,
+            self.builder.ins().iconst(self.ptr, qs.as_ptr() as i64),
+            self.builder.ins().stack_addr(self.ptr, ds, 0),
+            self.builder.ins().iconst(self.ptr, instrs.len() as i64),
+        ];
+        self.builder.ins().call(self.push_deferred, &args);
+        self.builder.ins().jump(next_block, &[]);
+        self.qs.push(qs);
+
+        self.builder.switch_to_block(next_block);
+    }
+
+    fn is_const(&self, slot: Slot) -> Option<Logic> {
+        let index = (slot as usize).checked_sub(self.num_wires)?;
+        Logic_Variants.get(index).copied()
+    }
+
+    fn iconst(&mut self, ty: Type, value: bool) -> Value {
+        if ty.is_vector() {
+            let lane = self.builder.ins().iconst(ty.lane_type(), value as i64);
+            self.builder.ins().splat(ty, lane)
+        } else {
+            self.builder.ins().iconst(ty, value as i64)
+        }
+    }
+
+    fn const_planes(&mut self, logic: Logic) -> Planes {
+        Planes {
+            may_be_0: self.iconst(SCALAR, logic != Logic::_1),
+            may_be_1: self.iconst(SCALAR, logic != Logic::_0),
+        }
+    }
+
+    fn load(&mut self, slot: Slot) -> Planes {
+        if let Some(planes) = self.cur_planes.get(&slot) {
+            return *planes;
+        }
+
+        let planes = match self.is_const(slot) {
+            Some(logic) => self.const_planes(logic),
+            None => self.load_from(self.cur, slot, SCALAR),
+        };
+        self.cur_planes.insert(slot, planes);
+        planes
+    }
+
+    fn load_prev(&mut self, slot: Slot) -> Planes {
+        match self.is_const(slot) {
+            Some(logic) => self.const_planes(logic),
+            None => self.load_from(self.prev, slot, SCALAR),
+        }
+    }
+
+    fn load_from(&mut self, wires: Value, slot: Slot, ty: Type) -> Planes {
+        let logic = match ty.is_vector() {
+            true => {
+                let flags = MemFlags::new().with_notrap();
+                self.builder.ins().load(ty, flags, wires, slot as i32)
+            }
+            false => self
+                .builder
+                .ins()
+                .uload8(ty, MemFlags::trusted(), wires, slot as i32),
+        };
+
+        // _0 => (1, 0), _1 => (0, 1), X => (1, 1)
+        let one = self.iconst(ty, true);
+        let value = self.builder.ins().band(logic, one);
+        let may_be_0 = self.builder.ins().bxor(value, one);
+        let may_be_1 = self.builder.ins().umin(logic, one);
+        Planes { may_be_0, may_be_1 }
+    }
+
+    fn store(&mut self, slot: Slot, planes: Planes) {
+        let logic = self.encode_logic(planes);
+        self.builder
+            .ins()
+            .istore8(MemFlags::trusted(), logic, self.cur, slot as i32);
+        self.cur_planes.insert(slot, planes);
+    }
+
+    fn store_vector(&mut self, slot: Slot, planes: Planes) {
+        let logic = self.encode_logic(planes);
+        let flags = MemFlags::new().with_notrap();
+        self.builder
+            .ins()
+            .store(flags, logic, self.cur, slot as i32);
+        for lane in 0..VECTOR_LANES as Slot {
+            self.cur_planes.remove(&(slot + lane));
+        }
+    }
+
+    /// the [Logic] repr of the planes
+    fn encode_logic(&mut self, planes: Planes) -> Value {
+        // (1, 0) => 0, (0, 1) => 1, (1, 1) => 2
+        let unknown = self.builder.ins().band(planes.may_be_0, planes.may_be_1);
+        self.builder.ins().iadd(planes.may_be_1, unknown)
+    }
+
+    /// `1` if the planes are `logic`, otherwise `0`
+    fn is(&mut self, planes: Planes, logic: Logic) -> Value {
+        match logic {
+            Logic::_0 => self
+                .builder
+                .ins()
+                .band_not(planes.may_be_0, planes.may_be_1),
+            Logic::_1 => self
+                .builder
+                .ins()
+                .band_not(planes.may_be_1, planes.may_be_0),
+            Logic::X => self.builder.ins().band(planes.may_be_0, planes.may_be_1),
+        }
+    }
+
+    fn eval(&mut self, ty: Type, inputs: &[Planes], table: impl Fn(&[Logic]) -> Logic) -> Planes {
+        let num_inputs = inputs.len();
+        let bools_of = |minterm: usize| -> SmallVec<[bool; 3]> {
+            (0..num_inputs).map(|i| (minterm >> i) & 1 == 1).collect()
+        };
+        let logics_of = |bools: &[bool]| -> SmallVec<[Logic; 3]> {
+            bools.iter().map(|it| Logic::from(*it)).collect()
+        };
+
+        if resolves_x(num_inputs, &table) {
+            let num_minterms = 1 << num_inputs;
+            let ones: Vec<usize> = (0..num_minterms)
+                .filter(|minterm| table(&logics_of(&bools_of(*minterm))) == Logic::_1)
+                .collect();
+            let zeros: Vec<usize> = (0..num_minterms)
+                .filter(|minterm| table(&logics_of(&bools_of(*minterm))) == Logic::_0)
+                .collect();
+
+            // the output may be 1 if any combination the inputs may be evaluates to 1
+            let may_be_1 = self.sum_of_products(ty, inputs, &minimize(num_inputs, &ones));
+            let may_be_0 = self.sum_of_products(ty, inputs, &minimize(num_inputs, &zeros));
+            return Planes { may_be_0, may_be_1 };
+        }
+
+        self.eval_minterms(ty, inputs, table)
+    }
+
+    fn sum_of_products(&mut self, ty: Type, inputs: &[Planes], cubes: &[Cube]) -> Value {
+        let mut sum: Option<Value> = None;
+        for cube in cubes.iter() {
+            let mut product: Option<Value> = None;
+            for (planes, bit) in inputs.iter().zip(cube.iter()) {
+                let plane = match bit {
+                    Some(false) => planes.may_be_0,
+                    Some(true) => planes.may_be_1,
+                    None => continue,
+                };
+                product = Some(match product {
+                    Some(product) => self.builder.ins().band(product, plane),
+                    None => plane,
+                });
+            }
+
+            let product = match product {
+                Some(product) => product,
+                None => return self.iconst(ty, true),
+            };
+            sum = Some(match sum {
+                Some(sum) => self.builder.ins().bor(sum, product),
+                None => product,
+            });
+        }
+        sum.unwrap_or_else(|| self.iconst(ty, false))
+    }
+
+    /// the union of all input combinations, including `X`, that evaluate to `1` or `X`
+    fn eval_minterms(
+        &mut self,
+        ty: Type,
+        inputs: &[Planes],
+        table: impl Fn(&[Logic]) -> Logic,
+    ) -> Planes {
+        let masks: Vec<[Value; Logic_Variants.len()]> = inputs
+            .iter()
+            .map(|planes| Logic_Variants.map(|logic| self.is(*planes, logic)))
+            .collect();
+
+        let mut value: Option<Value> = None;
+        let mut unknown: Option<Value> = None;
+
+        let num_minterms = Logic_Variants.len().pow(inputs.len() as u32);
+        let mut logics: Vec<Logic> = vec![Logic::_0; inputs.len()];
+        for minterm in 0..num_minterms {
+            let mut rest = minterm;
+            for logic in logics.iter_mut() {
+                *logic = Logic_Variants[rest % Logic_Variants.len()];
+                rest /= Logic_Variants.len();
+            }
+
+            let plane = match table(&logics) {
+                Logic::_0 => continue,
+                Logic::_1 => &mut value,
+                Logic::X => &mut unknown,
+            };
+
+            let mut term = masks[0][logics[0] as usize];
+            for i in 1..logics.len() {
+                term = self.builder.ins().band(term, masks[i][logics[i] as usize]);
+            }
+
+            *plane = Some(match *plane {
+                Some(acc) => self.builder.ins().bor(acc, term),
+                None => term,
+            });
+        }
+
+        let value = value.unwrap_or_else(|| self.iconst(ty, false));
+        let unknown = unknown.unwrap_or_else(|| self.iconst(ty, false));
+        let one = self.iconst(ty, true);
+        Planes {
+            may_be_0: self.builder.ins().bxor(value, one),
+            may_be_1: self.builder.ins().bor(value, unknown),
+        }
+    }
+}
+
+/// the inputs and the output of a bit operation
+fn operands(instr: &Instr) -> Option<(SmallVec<[Slot; 3]>, Slot)> {
+    match *instr {
+        Instr::Unary { a, y, .. } => Some((smallvec::smallvec![a], y)),
+        Instr::Binary { a, b, y, .. } => Some((smallvec::smallvec![a, b], y)),
+        Instr::Ternary { a, b, c, y, .. } => Some((smallvec::smallvec![a, b, c], y)),
+        Instr::DFlipFlop { .. } => None,
+    }
+}
+
+fn is_same_op(a: &Instr, b: &Instr) -> bool {
+    match (a, b) {
+        (Instr::Unary { op: a, .. }, Instr::Unary { op: b, .. }) => a == b,
+        (Instr::Binary { op: a, .. }, Instr::Binary { op: b, .. }) => a == b,
+        (Instr::Ternary { op: a, .. }, Instr::Ternary { op: b, .. }) => a == b,
+        _ => false,
+    }
+}
+
+/// whether every input combination with `X` evaluates to the common value of all its boolean
+/// resolutions, or `X` if they differ
+fn resolves_x(num_inputs: usize, table: &impl Fn(&[Logic]) -> Logic) -> bool {
+    let num_minterms = Logic_Variants.len().pow(num_inputs as u32);
+    (0..num_minterms).all(|minterm| {
+        let logics: SmallVec<[Logic; 3]> = (0..num_inputs)
+            .map(|i| Logic_Variants[minterm / Logic_Variants.len().pow(i as u32) % 3])
+            .collect();
+
+        let mut resolutions: SmallVec<[SmallVec<[Logic; 3]>; 8]> =
+            smallvec::smallvec![SmallVec::new()];
+        for logic in logics.iter() {
+            resolutions = resolutions
+                .into_iter()
+                .flat_map(|prefix| {
+                    Logic::to_bool_set(*logic).into_iter().map(move |bool| {
+                        let mut resolution = prefix.clone();
+                        resolution.push(Logic::from(bool));
+                        resolution
+                    })
+                })
+                .collect();
+        }
+
+        let bools: Vec<bool> = resolutions
+            .iter()
+            .map(|resolution| table(resolution) == Logic::_1)
+            .collect();
+        table(&logics) == Logic::from_bool_set(&bools)
+    })
+}
+
+/// the prime implicants covering all `minterms`, chosen greedily
+fn minimize(num_inputs: usize, minterms: &[usize]) -> Vec<Cube> {
+    let mut cubes: Vec<Cube> = minterms
+        .iter()
+        .map(|minterm| {
+            (0..num_inputs)
+                .map(|i| Some((minterm >> i) & 1 == 1))
+                .collect()
+        })
+        .collect();
+
+    let mut primes: Vec<Cube> = Vec::new();
+    while !cubes.is_empty() {
+        let mut is_merged = vec![false; cubes.len()];
+        let mut merged: Vec<Cube> = Vec::new();
+
+        for i in 0..cubes.len() {
+            for j in i + 1..cubes.len() {
+                let diffs: Vec<usize> = (0..num_inputs)
+                    .filter(|k| cubes[i][*k] != cubes[j][*k])
+                    .collect();
+                if let [k] = diffs[..]
+                    && cubes[i][k].is_some()
+                    && cubes[j][k].is_some()
+                {
+                    let mut cube = cubes[i].clone();
+                    cube[k] = None;
+                    if !merged.contains(&cube) {
+                        merged.push(cube);
+                    }
+                    is_merged[i] = true;
+                    is_merged[j] = true;
+                }
+            }
+        }
+
+        for (cube, is_merged) in cubes.into_iter().zip(is_merged) {
+            if !is_merged && !primes.contains(&cube) {
+                primes.push(cube);
+            }
+        }
+        cubes = merged;
+    }
+
+    let covers = |cube: &Cube, minterm: usize| {
+        cube.iter()
+            .enumerate()
+            .all(|(i, bit)| bit.is_none_or(|bit| bit == ((minterm >> i) & 1 == 1)))
+    };
+
+    let mut uncovered: Vec<usize> = minterms.to_vec();
+    let mut cover: Vec<Cube> = Vec::new();
+    while !uncovered.is_empty() {
+        let best = primes
+            .iter()
+            .max_by_key(|cube| uncovered.iter().filter(|it| covers(cube, **it)).count())
+            .unwrap()
+            .clone();
+        uncovered.retain(|it| !covers(&best, *it));
+        cover.push(best);
+    }
+    cover
+}
@@ -0,0 +1,297 @@
+use crate::common::SimError;
+use crate::model::HCell;
+use crate::model::HWire;
+use crate::model::HWireOrLogic;
+use crate::model::Module;
+use crate::model::ModuleInPort;
+use crate::model::ModuleOutPort;
+use crate::sim::CellSimModel;
+use crate::sim::Logic;
+use crate::sim::OP_FNS;
+use crate::sim::OpFns;
+use crate::sim::RegisterInit;
+use crate::sim::StateRef;
+use crate::sim::XPolicy;
+use crate::sim::compute_cell_graph;
+use crate::sim::compute_cell_update_order;
+use crate::sim::compute_initial_wires;
+use crate::sim::compute_num_wires;
+use crate::sim::compute_wire_graph;
+
+pub const NUM_LANES: usize = 64;
+
+/// [NUM_LANES] independent [Logic] values packed into two bit planes.
+/// A lane is `X` if its `unknown` bit is set, otherwise it is the lane's `value` bit.
+/// The `value` bit of an unknown lane is always `0`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lanes {
+    pub value: u64,
+    pub unknown: u64,
+}
+
+impl Lanes {
+    pub const X: Lanes = Lanes {
+        value: 0,
+        unknown: !0,
+    };
+
+    pub const fn splat(logic: Logic) -> Lanes {
+        match logic {
+            Logic::_0 => Lanes {
+                value: 0,
+                unknown: 0,
+            },
+            Logic::_1 => Lanes {
+                value: !0,
+                unknown: 0,
+            },
+            Logic::X => Lanes::X,
+        }
+    }
+
+    /// the same lanes with the `value` bits of the unknown lanes cleared, so a lane built from
+    /// arbitrary bit planes reads as `X` everywhere
+    pub const fn normalized(self) -> Lanes {
+        Lanes {
+            value: self.value & !self.unknown,
+            unknown: self.unknown,
+        }
+    }
+
+    pub fn get(&self, lane: usize) -> Logic {
+        match ((self.unknown >> lane) & 1, (self.value >> lane) & 1) {
+            (1, _) => Logic::X,
+            (_, value) => value.into(),
+        }
+    }
+
+    pub fn set(&mut self, lane: usize, logic: Logic) {
+        *self = Lanes::select(1 << lane, Lanes::splat(logic), *self);
+    }
+
+    /// the lanes in `mask` are taken from `a`, all others from `b`
+    pub fn select(mask: u64, a: Lanes, b: Lanes) -> Lanes {
+        Lanes {
+            value: (a.value & mask) | (b.value & !mask),
+            unknown: (a.unknown & mask) | (b.unknown & !mask),
+        }
+    }
+
+    /// the mask of all lanes that are `logic`
+    pub fn mask(&self, logic: Logic) -> u64 {
+        match logic {
+            Logic::_0 => !self.value & !self.unknown,
+            Logic::_1 => self.value,
+            Logic::X => self.unknown,
+        }
+    }
+
+    /// the lanes in `mask` become `logic`, all others are left unchanged
+    pub fn or_mask(&mut self, mask: u64, logic: Logic) {
+        match logic {
+            Logic::_0 => (),
+            Logic::_1 => self.value |= mask,
+            Logic::X => self.unknown |= mask,
+        }
+    }
+}
+
+pub struct LaneSimState {
+    pub wires: [Vec<Lanes>; 2],
+    pub set_wires_deferred: Vec<(HWire, Lanes)>,
+    pub ops: &'static OpFns,
+}
+
+impl LaneSimState {
+    pub fn get_wires_or_logic(
+        &self,
+        state: StateRef,
+        h_wires: &[HWireOrLogic],
+        lanes: &mut [Lanes],
+    ) {
+        if h_wires.len() != lanes.len() {
+            panic!("lengths do not match");
+        }
+
+        for i in 0..h_wires.len() {
+            lanes[i] = match h_wires[i] {
+                HWireOrLogic::HWire(h_wire) => self.wires[state as usize][h_wire],
+                HWireOrLogic::Logic(logic) => Lanes::splat(logic),
+            };
+        }
+    }
+
+    pub fn get_wires(&self, state: StateRef, h_wires: &[HWire], lanes: &mut [Lanes]) {
+        if h_wires.len() != lanes.len() {
+            panic!("lengths do not match");
+        }
+
+        for i in 0..h_wires.len() {
+            lanes[i] = self.wires[state as usize][h_wires[i]];
+        }
+    }
+
+    pub fn set_wires(&mut self, state: StateRef, h_wires: &[HWire], lanes: &[Lanes]) {
+        if h_wires.len() != lanes.len() {
+            panic!("lengths do not match");
+        }
+
+        for i in 0..h_wires.len() {
+            self.wires[state as usize][h_wires[i]] = lanes[i];
+        }
+    }
+
+    pub fn set_wires_deferred(&mut self, h_wires: &[HWire], lanes: &[Lanes]) {
+        if h_wires.len() != lanes.len() {
+            panic!("lengths do not match");
+        }
+
+        for i in 0..h_wires.len() {
+            self.set_wires_deferred.push((h_wires[i], lanes[i]));
+        }
+    }
+}
+
+/// Simulates [NUM_LANES] independent stimuli at once, each wire holds one [Lanes] word.
+/// Every lane behaves exactly like a [crate::sim::Sim] would.
+pub struct LaneSim<'m> {
+    frame: usize,
+    module: &'m Module,
+    update_order: Vec<HCell>,
+    sim_state: LaneSimState,
+}
+
+impl<'m> LaneSim<'m> {
+    pub fn new(module: &'m Module) -> Self {
+        let num_wires: usize = compute_num_wires(module);
+
+        let wire_nodes = compute_wire_graph(module, num_wires);
+        let cell_nodes = compute_cell_graph(module, &wire_nodes);
+        let update_order = compute_cell_update_order(module, &cell_nodes);
+
+        let wires: Vec<Lanes> =
+            compute_initial_wires(module, num_wires, RegisterInit::X, XPolicy::Precise)
+                .into_iter()
+                .map(Lanes::splat)
+                .collect();
+
+        LaneSim {
+            frame: 0,
+            module,
+            update_order,
+            sim_state: LaneSimState {
+                wires: [wires.clone(), wires],
+                set_wires_deferred: Vec::new(),
+                ops: &OP_FNS,
+            },
+        }
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn simulate(&mut self) -> Result<(), SimError> {
+        loop {
+            for h_cell in self.update_order.iter() {
+                let cell = &self.module.cells[*h_cell];
+
+                cell.simulate_lanes(&mut self.sim_state);
+            }
+
+            let [prev_wires, cur_wires] = &mut self.sim_state.wires;
+            prev_wires.copy_from_slice(cur_wires);
+
+            if self.sim_state.set_wires_deferred.is_empty() {
+                break;
+            }
+
+            for (h_wire, lanes) in self.sim_state.set_wires_deferred.iter() {
+                cur_wires[*h_wire] = *lanes;
+            }
+            self.sim_state.set_wires_deferred.clear();
+        }
+
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// the lanes are [Lanes::normalized], as the cells rely on it
+    pub fn set<const L: usize>(&mut self, port: &ModuleInPort<L>, lanes: [Lanes; L]) {
+        self.sim_state
+            .set_wires(StateRef::Cur, &port.wires, &lanes.map(Lanes::normalized));
+    }
+
+    /// the lanes are [Lanes::normalized], as the cells rely on it
+    pub fn set_dynamic(&mut self, port: &ModuleInPort, lanes: &[Lanes]) {
+        if port.wires.len() != lanes.len() {
+            panic!("lengths do not match");
+        }
+
+        let wires = &mut self.sim_state.wires[StateRef::Cur as usize];
+        for (h_wire, lanes) in port.wires.iter().zip(lanes.iter()) {
+            wires[*h_wire] = lanes.normalized();
+        }
+    }
+
+    /// sets the value of a single lane, leaving the other lanes unchanged
+    pub fn set_lane<E: Copy + Into<Logic>, const L: usize>(
+        &mut self,
+        port: &ModuleInPort<L>,
+        lane: usize,
+        logics: [E; L],
+    ) {
+        let wires = &mut self.sim_state.wires[StateRef::Cur as usize];
+        for (h_wire, logic) in port.wires.iter().zip(logics.iter()) {
+            wires[*h_wire].set(lane, (*logic).into());
+        }
+    }
+
+    pub fn set_lane_dynamic<E: Copy + Into<Logic>>(
+        &mut self,
+        port: &ModuleInPort,
+        lane: usize,
+        logics: &[E],
+    ) {
+        if port.wires.len() != logics.len() {
+            panic!("lengths do not match");
+        }
+
+        let wires = &mut self.sim_state.wires[StateRef::Cur as usize];
+        for (h_wire, logic) in port.wires.iter().zip(logics.iter()) {
+            wires[*h_wire].set(lane, (*logic).into());
+        }
+    }
+
+    pub fn get<const L: usize>(&self, port: &ModuleOutPort<L>) -> [Lanes; L] {
+        let mut lanes: [Lanes; L] = [Lanes::X; L];
+        self.sim_state
+            .get_wires(StateRef::Cur, &port.wires, &mut lanes);
+        lanes
+    }
+
+    pub fn get_dynamic(&self, port: &ModuleOutPort) -> Vec<Lanes> {
+        let mut lanes = vec![Lanes::X; port.wires.len()];
+        self.sim_state
+            .get_wires(StateRef::Cur, &port.wires, &mut lanes);
+        lanes
+    }
+
+    /// reads the value of a single lane
+    pub fn get_lane<const L: usize>(&self, port: &ModuleOutPort<L>, lane: usize) -> [Logic; L] {
+        let mut logics: [Logic; L] = [Logic::X; L];
+        let wires = &self.sim_state.wires[StateRef::Cur as usize];
+        for (logic, h_wire) in logics.iter_mut().zip(port.wires.iter()) {
+            *logic = wires[*h_wire].get(lane);
+        }
+        logics
+    }
+
+    pub fn get_lane_dynamic(&self, port: &ModuleOutPort, lane: usize) -> Vec<Logic> {
+        let wires = &self.sim_state.wires[StateRef::Cur as usize];
+        port.wires
+            .iter()
+            .map(|h_wire| wires[*h_wire].get(lane))
+            .collect()
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(non_upper_case_globals)]
 
+use crate::common::Random;
 use crate::common::Vec2;
 use crate::define_enum;
 use core::str;
@@ -12,8 +13,8 @@ define_enum![enum Logic repr(u8) {
 impl Logic {
     pub fn to_bits<const L: usize>(integer: isize) -> [Logic; L] {
         let mut bits: [Logic; L] = [Logic::X; L];
-        for i in 0..L {
-            bits[i] = ((integer >> i) & 0b1).into();
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = ((integer >> i) & 0b1).into();
         }
         bits
     }
@@ -25,6 +26,7 @@ impl Logic {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Option<Logic> {
         match value {
             "0" => Some(Logic::_0),
@@ -33,6 +35,22 @@ impl Logic {
         }
     }
 
+    /// `X` is read as `0`
+    pub fn to_two_state(self) -> Logic {
+        match self {
+            Logic::X => Logic::_0,
+            logic => logic,
+        }
+    }
+
+    /// `X` is read as `0` or `1` at random
+    pub fn to_random_two_state(self, random: &mut Random) -> Logic {
+        match self {
+            Logic::X => random.logic(),
+            logic => logic,
+        }
+    }
+
     pub fn to_bool_set(logic: Logic) -> Vec2<bool> {
         match logic {
             Logic::_0 => smallvec![false],
@@ -42,7 +60,7 @@ impl Logic {
     }
 
     pub fn from_bool_set(logic: &[bool]) -> Logic {
-        if logic.len() == 0 {
+        if logic.is_empty() {
             panic!("illega argument");
         }
         let first = logic[0];
@@ -63,7 +81,7 @@ impl Logic {
                 return false;
             }
         }
-        return true;
+        true
     }
 }
 
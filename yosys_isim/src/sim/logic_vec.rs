@@ -0,0 +1,307 @@
+use crate::common::SimError;
+use crate::sim::IntValue;
+use crate::sim::Logic;
+use crate::sim::int_value::logics_to_words;
+use crate::sim::int_value::words_to_logics;
+use std::fmt;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::str::FromStr;
+
+/// A vector of [Logic]s, LSB first, that reads and prints Verilog literals like
+/// `8'b10x1_0zz1`, `16'hDEAD` or `4'd9`. `Z` digits are read as `X`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogicVec(pub Vec<Logic>);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Radix {
+    Bin,
+    Oct,
+    Dec,
+    Hex,
+}
+
+impl Radix {
+    fn of(char: char) -> Option<Radix> {
+        match char.to_ascii_lowercase() {
+            'b' => Some(Radix::Bin),
+            'o' => Some(Radix::Oct),
+            'd' => Some(Radix::Dec),
+            'h' => Some(Radix::Hex),
+            _ => None,
+        }
+    }
+
+    fn char(self) -> char {
+        match self {
+            Radix::Bin => 'b',
+            Radix::Oct => 'o',
+            Radix::Dec => 'd',
+            Radix::Hex => 'h',
+        }
+    }
+
+    /// the bits per digit, `None` for [Radix::Dec]
+    fn bits(self) -> Option<usize> {
+        match self {
+            Radix::Bin => Some(1),
+            Radix::Oct => Some(3),
+            Radix::Dec => None,
+            Radix::Hex => Some(4),
+        }
+    }
+}
+
+impl LogicVec {
+    /// `value` truncated or zero extended to `width`
+    pub fn from_u64(width: usize, value: u64) -> Self {
+        LogicVec(words_to_logics(&[value], false, width))
+    }
+
+    /// Truncated or extended to `width`, with `X` if the MSB is `X` and `0` otherwise,
+    /// like an unsized Verilog literal.
+    pub fn resized(mut self, width: usize) -> LogicVec {
+        let extension = match self.last() {
+            Some(Logic::X) => Logic::X,
+            _ => Logic::_0,
+        };
+        self.0.resize(width, extension);
+        self
+    }
+
+    /// whether [LogicVec::resized] to `width` drops no `1` bit
+    pub fn fits(&self, width: usize) -> bool {
+        self.iter().skip(width).all(|it| *it != Logic::_1)
+    }
+
+    pub fn to_int(&self) -> IntValue<Vec<u64>> {
+        logics_to_words(&self.0)
+    }
+
+    /// Prints the Verilog literal in `radix`, MSB first. A digit is `x` if all of its bits are
+    /// `X` and `X` if only some are.
+    pub fn to_literal(&self, radix: Radix) -> String {
+        format!(
+            "{}'{}{}",
+            self.len(),
+            radix.char(),
+            self.digits(radix, false)
+        )
+    }
+
+    fn digits(&self, radix: Radix, upper_case: bool) -> String {
+        let x_digit = |logics: &[Logic]| match logics.iter().all(|it| *it == Logic::X) {
+            true => Some('x'),
+            false => logics.contains(&Logic::X).then_some('X'),
+        };
+
+        if self.is_empty() {
+            return "0".to_string();
+        }
+
+        let Some(bits) = radix.bits() else {
+            return match x_digit(self) {
+                Some(char) => char.to_string(),
+                None => words_to_decimal(self.to_int().unwrap()),
+            };
+        };
+
+        let mut digits: Vec<char> = self
+            .chunks(bits)
+            .map(|logics| {
+                x_digit(logics).unwrap_or_else(|| {
+                    let digit = logics
+                        .iter()
+                        .rev()
+                        .fold(0, |digit, it| (digit << 1) | (*it == Logic::_1) as u32);
+                    let char = char::from_digit(digit, 16).unwrap();
+                    match upper_case {
+                        true => char.to_ascii_uppercase(),
+                        false => char,
+                    }
+                })
+            })
+            .collect();
+        digits.reverse();
+        digits.into_iter().collect()
+    }
+}
+
+impl FromStr for LogicVec {
+    type Err = SimError;
+
+    /// Reads `[size]'[s]<radix><digits>` or plain decimal digits. Unsized literals have
+    /// 32 bits, or more if their digits need more. Literals are truncated to their size,
+    /// or extended with `0`, or with `X` if their leftmost digit is `x` or `z`.
+    fn from_str(literal: &str) -> Result<Self, Self::Err> {
+        let illegal = |msg: &'static str| SimError::IllegalLiteral {
+            literal: literal.to_string(),
+            msg,
+        };
+
+        let trimmed = literal.trim();
+        let (size, radix, digits) = match trimmed.split_once('\'') {
+            Some((size, rest)) => {
+                let size = match size.trim() {
+                    "" => None,
+                    size => match size.parse::<usize>() {
+                        Ok(size) if size > 0 => Some(size),
+                        _ => return Err(illegal("the size is not a positive number")),
+                    },
+                };
+                let rest = rest.strip_prefix(['s', 'S']).unwrap_or(rest);
+                let mut chars = rest.chars();
+                let radix = chars
+                    .next()
+                    .and_then(Radix::of)
+                    .ok_or_else(|| illegal("expected one of the radixes b, o, d, h"))?;
+                (size, radix, chars.as_str())
+            }
+            None => (None, Radix::Dec, trimmed),
+        };
+
+        let digits: Vec<char> = digits
+            .trim()
+            .chars()
+            .filter(|it| *it != '_')
+            .map(|it| it.to_ascii_lowercase())
+            .collect();
+        if digits.is_empty() {
+            return Err(illegal("no digits"));
+        }
+        let is_x = |char: char| matches!(char, 'x' | 'z' | '?');
+
+        // LSB first
+        let mut logics: Vec<Logic> = Vec::new();
+        match radix.bits() {
+            Some(bits) => {
+                for char in digits.iter().rev() {
+                    if is_x(*char) {
+                        logics.extend(std::iter::repeat_n(Logic::X, bits));
+                        continue;
+                    }
+                    let digit = char
+                        .to_digit(1 << bits)
+                        .ok_or_else(|| illegal("illegal digit"))?;
+                    logics.extend((0..bits).map(|i| Logic::from((digit >> i) & 1)));
+                }
+            }
+            None if digits.len() == 1 && is_x(digits[0]) => {
+                logics.push(Logic::X);
+            }
+            None => {
+                let mut words: Vec<u64> = vec![0];
+                for char in digits.iter() {
+                    let digit = char.to_digit(10).ok_or_else(|| illegal("illegal digit"))?;
+                    let mut carry = digit as u128;
+                    for word in words.iter_mut() {
+                        let product = *word as u128 * 10 + carry;
+                        *word = product as u64;
+                        carry = product >> 64;
+                    }
+                    if carry > 0 {
+                        words.push(carry as u64);
+                    }
+                }
+                logics = words_to_logics(&words, false, words.len() * 64);
+                while logics.len() > 1 && logics.last() == Some(&Logic::_0) {
+                    logics.pop();
+                }
+            }
+        }
+
+        let width = size.unwrap_or(usize::max(32, logics.len()));
+        let extension = match is_x(digits[0]) {
+            true => Logic::X,
+            false => Logic::_0,
+        };
+        logics.resize(width, extension);
+
+        Ok(LogicVec(logics))
+    }
+}
+
+fn words_to_decimal(mut words: Vec<u64>) -> String {
+    let mut digits: Vec<char> = Vec::new();
+    loop {
+        let mut remainder: u128 = 0;
+        for word in words.iter_mut().rev() {
+            let dividend = (remainder << 64) | *word as u128;
+            *word = (dividend / 10) as u64;
+            remainder = dividend % 10;
+        }
+        digits.push(char::from_digit(remainder as u32, 10).unwrap());
+        if words.iter().all(|it| *it == 0) {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// the binary literal
+impl fmt::Display for LogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_literal(Radix::Bin))
+    }
+}
+
+impl fmt::Binary for LogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_literal(Radix::Bin))
+    }
+}
+
+impl fmt::Octal for LogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_literal(Radix::Oct))
+    }
+}
+
+impl fmt::LowerHex for LogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_literal(Radix::Hex))
+    }
+}
+
+impl fmt::UpperHex for LogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}'h{}", self.len(), self.digits(Radix::Hex, true))
+    }
+}
+
+impl Deref for LogicVec {
+    type Target = Vec<Logic>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for LogicVec {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<Logic>> for LogicVec {
+    fn from(value: Vec<Logic>) -> Self {
+        LogicVec(value)
+    }
+}
+
+impl<const L: usize> From<[Logic; L]> for LogicVec {
+    fn from(value: [Logic; L]) -> Self {
+        LogicVec(value.to_vec())
+    }
+}
+
+impl From<&[Logic]> for LogicVec {
+    fn from(value: &[Logic]) -> Self {
+        LogicVec(value.to_vec())
+    }
+}
+
+impl FromIterator<Logic> for LogicVec {
+    fn from_iter<T: IntoIterator<Item = Logic>>(iter: T) -> Self {
+        LogicVec(iter.into_iter().collect())
+    }
+}
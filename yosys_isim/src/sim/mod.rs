@@ -1,11 +1,43 @@
 mod cell;
+mod clock;
+mod config;
+mod design;
 mod edge;
 mod fns;
+mod formal;
+mod history;
+mod int_value;
+#[cfg(feature = "jit")]
+mod jit;
+mod lanes;
 mod logic;
+mod logic_vec;
+mod partition;
+mod port_handle;
+mod scheduler;
+#[allow(clippy::module_inception)]
 mod sim;
+mod snapshot;
+mod tape;
+mod vcd;
+mod watch;
 
 pub use cell::*;
+pub use clock::*;
+pub use config::*;
+pub use design::*;
 pub use edge::*;
 pub use fns::*;
+pub use formal::FormalEvent;
+pub use int_value::IntValue;
+#[cfg(feature = "jit")]
+pub use jit::*;
+pub use lanes::*;
 pub use logic::*;
+pub use logic_vec::*;
+pub use port_handle::*;
 pub use sim::*;
+pub use snapshot::*;
+pub use tape::*;
+pub use vcd::*;
+pub use watch::*;
@@ -0,0 +1,198 @@
+use crate::model::HCell;
+use crate::model::HWire;
+use crate::model::Module;
+use crate::sim::CellNode;
+use crate::sim::CellSimModel;
+use crate::sim::CellWires;
+use crate::sim::SimState;
+use crate::sim::StateRef;
+use crate::sim::Tape;
+use rayon::ThreadPool;
+use rayon::iter::IndexedParallelIterator;
+use rayon::iter::IntoParallelRefIterator;
+use rayon::iter::IntoParallelRefMutIterator;
+use rayon::iter::ParallelIterator;
+
+/// Cells that share no wire with the cells of any other partition.
+/// Each partition simulates its own copy of the wires, so partitions can run concurrently
+/// and produce the same results in any order.
+/// The wires themselves are owned by each [crate::sim::Sim], see [Partitions::new_sim_states].
+pub(super) struct Partition {
+    update_order: Vec<HCell>,
+    tape: Option<Tape>,
+    /// the wires driven by the cells of this partition
+    h_out_wires: Vec<HWire>,
+}
+
+pub(super) struct Partitions {
+    pool: ThreadPool,
+    h_in_wires: Vec<HWire>,
+    partitions: Vec<Partition>,
+}
+
+impl Partitions {
+    pub fn new(
+        module: &Module,
+        num_wires: usize,
+        pool: ThreadPool,
+        partition_update_orders: Vec<Vec<HCell>>,
+        with_tape: bool,
+    ) -> Self {
+        let partitions = partition_update_orders
+            .into_iter()
+            .map(|update_order| {
+                let tape = with_tape.then(|| Tape::compile(module, &update_order, num_wires));
+                let h_out_wires = update_order
+                    .iter()
+                    .flat_map(|h_cell| CellWires::get_out_port_h_wires(&module.cells[*h_cell]))
+                    .collect();
+                Partition {
+                    update_order,
+                    tape,
+                    h_out_wires,
+                }
+            })
+            .collect();
+
+        Partitions {
+            pool,
+            h_in_wires: module
+                .in_ports
+                .iter()
+                .flat_map(|it| it.wires.iter().cloned())
+                .collect(),
+            partitions,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// one [SimState] per partition
+    pub fn new_sim_states(
+        &self,
+        new_sim_state: impl Fn(Option<&Tape>) -> SimState,
+    ) -> Vec<SimState> {
+        self.partitions
+            .iter()
+            .map(|partition| new_sim_state(partition.tape.as_ref()))
+            .collect()
+    }
+
+    /// simulates one frame of every partition and merges the results into `sim_state`
+    pub fn simulate(
+        &self,
+        module: &Module,
+        partition_sim_states: &mut [SimState],
+        sim_state: &mut SimState,
+    ) {
+        let [_, cur_wires] = &sim_state.wires;
+        for partition_sim_state in partition_sim_states.iter_mut() {
+            let partition_wires = &mut partition_sim_state.wires[StateRef::Cur as usize];
+            for h_wire in self.h_in_wires.iter() {
+                partition_wires[*h_wire] = cur_wires[*h_wire];
+            }
+        }
+
+        self.pool.install(|| {
+            self.partitions
+                .par_iter()
+                .zip(partition_sim_states.par_iter_mut())
+                .for_each(|(partition, partition_sim_state)| {
+                    partition.simulate(module, partition_sim_state)
+                })
+        });
+
+        let [prev_wires, cur_wires] = &mut sim_state.wires;
+        for (partition, partition_sim_state) in
+            self.partitions.iter().zip(partition_sim_states.iter())
+        {
+            let partition_wires = &partition_sim_state.wires[StateRef::Cur as usize];
+            for h_wire in partition.h_out_wires.iter() {
+                cur_wires[*h_wire] = partition_wires[*h_wire];
+            }
+        }
+        prev_wires.copy_from_slice(cur_wires);
+    }
+}
+
+impl Partition {
+    fn simulate(&self, module: &Module, sim_state: &mut SimState) {
+        loop {
+            match &self.tape {
+                Some(tape) => tape.run(sim_state),
+                None => {
+                    for h_cell in self.update_order.iter() {
+                        module.cells[*h_cell].simulate(sim_state);
+                    }
+                }
+            }
+
+            let [prev_wires, cur_wires] = &mut sim_state.wires;
+            prev_wires.copy_from_slice(cur_wires);
+
+            if sim_state.set_wires_deferred.is_empty() {
+                break;
+            }
+            for (h_wire, logic) in sim_state.set_wires_deferred.drain(..) {
+                cur_wires[h_wire] = logic;
+            }
+        }
+    }
+}
+
+/// Splits the cells into at most `num_partitions` partitions of weakly connected regions,
+/// balanced by their number of cells. Each partition keeps the relative `update_order`.
+pub(super) fn compute_partitions(
+    cell_nodes: &[CellNode],
+    update_order: &[HCell],
+    num_partitions: usize,
+) -> Vec<Vec<HCell>> {
+    let mut parents: Vec<HCell> = (0..cell_nodes.len()).collect();
+    fn find(parents: &mut [HCell], h_cell: HCell) -> HCell {
+        let mut root = h_cell;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        parents[h_cell] = root;
+        root
+    }
+
+    for (h_cell, cell_node) in cell_nodes.iter().enumerate() {
+        for h_next_cell in cell_node.h_next_cells.iter() {
+            let a = find(&mut parents, h_cell);
+            let b = find(&mut parents, *h_next_cell);
+            // the lower cell becomes the root, which keeps the regions deterministic
+            parents[usize::max(a, b)] = usize::min(a, b);
+        }
+    }
+
+    let mut regions: Vec<Vec<HCell>> = vec![Vec::new(); cell_nodes.len()];
+    for h_cell in update_order.iter() {
+        let root = find(&mut parents, *h_cell);
+        regions[root].push(*h_cell);
+    }
+    regions.retain(|it| !it.is_empty());
+
+    // the largest regions first, each into the currently smallest partition
+    regions.sort_by_key(|it| std::cmp::Reverse(it.len()));
+    let mut partitions: Vec<Vec<HCell>> =
+        vec![Vec::new(); usize::min(num_partitions, regions.len())];
+    for region in regions {
+        let smallest = (0..partitions.len())
+            .min_by_key(|index| partitions[*index].len())
+            .unwrap();
+        partitions[smallest].extend(region);
+    }
+
+    let mut ranks: Vec<usize> = vec![0; cell_nodes.len()];
+    for (rank, h_cell) in update_order.iter().enumerate() {
+        ranks[*h_cell] = rank;
+    }
+    for partition in partitions.iter_mut() {
+        partition.sort_by_key(|h_cell| ranks[*h_cell]);
+    }
+
+    partitions
+}
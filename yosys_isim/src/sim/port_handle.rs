@@ -0,0 +1,66 @@
+use crate::common::SimError;
+use crate::common::Vec4;
+use crate::model::HWire;
+use crate::model::ModuleInPort;
+use crate::model::ModuleOutPort;
+use crate::model::PortDirection;
+use crate::model::PortInfo;
+use std::marker::PhantomData;
+
+/// A port looked up by name at runtime, see [crate::sim::Sim::port].
+/// Unlike [ModuleInPort] and [ModuleOutPort] its width is not known at compile time.
+#[derive(Debug, Clone)]
+pub struct PortHandle {
+    pub module: String,
+    pub info: PortInfo,
+    pub(super) wires: Vec4<HWire>,
+}
+
+impl PortHandle {
+    pub fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    pub fn width(&self) -> usize {
+        self.info.width
+    }
+
+    pub fn direction(&self) -> PortDirection {
+        self.info.direction
+    }
+
+    pub fn signed(&self) -> bool {
+        self.info.signed
+    }
+
+    /// for the port accessors of [crate::sim::Sim] that take a [ModuleInPort]
+    pub fn to_in_port(&self) -> Result<ModuleInPort, SimError> {
+        self.check_direction(PortDirection::Input)?;
+        Ok(ModuleInPort {
+            name: self.info.name.clone(),
+            wires: self.wires.clone(),
+            dir: PhantomData,
+        })
+    }
+
+    /// for the port accessors of [crate::sim::Sim] that take a [ModuleOutPort]
+    pub fn to_out_port(&self) -> Result<ModuleOutPort, SimError> {
+        self.check_direction(PortDirection::Output)?;
+        Ok(ModuleOutPort {
+            name: self.info.name.clone(),
+            wires: self.wires.clone(),
+            dir: PhantomData,
+        })
+    }
+
+    pub(crate) fn check_direction(&self, direction: PortDirection) -> Result<(), SimError> {
+        match self.info.direction == direction {
+            true => Ok(()),
+            false => Err(SimError::WrongPortDirection {
+                module: self.module.clone(),
+                port: self.info.name.clone(),
+                direction: self.info.direction,
+            }),
+        }
+    }
+}
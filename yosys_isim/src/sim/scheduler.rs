@@ -0,0 +1,31 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Pending cells of the event driven scheduler, identified by their rank in the update order.
+pub(super) struct EventQueue {
+    ranks: BinaryHeap<Reverse<usize>>,
+    is_scheduled: Vec<bool>,
+}
+
+impl EventQueue {
+    pub(super) fn new(num_ranks: usize) -> Self {
+        EventQueue {
+            ranks: BinaryHeap::new(),
+            is_scheduled: vec![false; num_ranks],
+        }
+    }
+
+    pub(super) fn schedule(&mut self, rank: usize) {
+        if !self.is_scheduled[rank] {
+            self.is_scheduled[rank] = true;
+            self.ranks.push(Reverse(rank));
+        }
+    }
+
+    /// returns the lowest scheduled rank
+    pub(super) fn pop(&mut self) -> Option<usize> {
+        let Reverse(rank) = self.ranks.pop()?;
+        self.is_scheduled[rank] = false;
+        Some(rank)
+    }
+}
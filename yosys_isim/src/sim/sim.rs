@@ -1,24 +1,66 @@
 use crate::common::Set4;
 use crate::common::SimError;
+use crate::common::Vec4;
+use crate::model::FormalCell;
+use crate::model::FormalKind;
 use crate::model::HCell;
 use crate::model::HWire;
 use crate::model::HWireOrLogic;
 use crate::model::Module;
 use crate::model::ModuleInPort;
 use crate::model::ModuleOutPort;
+use crate::model::PortDirection;
 use crate::sim::CellSimModel;
 use crate::sim::CellWires;
+use crate::sim::Clock;
+use crate::sim::Design;
 use crate::sim::Edge;
+use crate::sim::Engine;
+use crate::sim::FormalEvent;
+use crate::sim::Halt;
+use crate::sim::HaltReason;
+use crate::sim::IntValue;
 use crate::sim::Logic;
-use crate::sim::OP_FNS;
+use crate::sim::LogicVec;
 use crate::sim::OpFns;
+use crate::sim::PortHandle;
+use crate::sim::Scheduler;
+use crate::sim::SimConfig;
+use crate::sim::Snapshot;
+use crate::sim::Tape;
+use crate::sim::WatchHit;
+use crate::sim::Watchpoint;
+use crate::sim::clock::ClockSource;
+use crate::sim::formal::FormalState;
+use crate::sim::history::HistoryLog;
+use crate::sim::int_value::logics_to_words;
+use crate::sim::int_value::sign_extend;
+use crate::sim::int_value::words_to_logics;
+use crate::sim::scheduler::EventQueue;
+use crate::sim::watch::WatchState;
 use core::panic;
 use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 
+/// The state of a single simulation of a [Design].
 pub struct Sim<'m> {
     frame: usize,
-    module: &'m Module,
-    update_order: Vec<HCell>,
+    /// only advanced by the [Clock]s
+    time: u64,
+    clocks: Vec<ClockSource>,
+    design: Arc<Design<'m>>,
+    event_queue: EventQueue,
+    needs_full_sweep: bool,
+    /// one per partition of the design, if it is simulated in parallel
+    partition_sim_states: Vec<SimState>,
+    /// only recorded if [SimConfig::history] is set
+    history: Option<HistoryLog>,
+    /// indexed by the id returned by [Sim::watch]
+    watches: Vec<Option<WatchState>>,
+    /// the watchpoints that fired in the last frame
+    watch_hits: Vec<WatchHit>,
+    formal: FormalState,
     sim_state: SimState,
 }
 
@@ -33,6 +75,39 @@ pub struct SimState {
     pub wires: [Vec<Logic>; 2],
     pub set_wires_deferred: Vec<(HWire, Logic)>,
     pub ops: &'static OpFns,
+    /// only tracked for [Scheduler::EventDriven]
+    pub changed_wires: Option<ChangedWires>,
+    /// the values of the forced wires, `None` if no wire is forced
+    pub forced_wires: Option<Vec<Option<Logic>>>,
+}
+
+/// The wires whose current value differs from the previous sub-frame.
+pub struct ChangedWires {
+    pub h_wires: Vec<HWire>,
+    is_changed: Vec<bool>,
+}
+
+impl ChangedWires {
+    fn new(num_wires: usize) -> Self {
+        ChangedWires {
+            h_wires: Vec::new(),
+            is_changed: vec![false; num_wires],
+        }
+    }
+
+    fn insert(&mut self, h_wire: HWire) {
+        if !self.is_changed[h_wire] {
+            self.is_changed[h_wire] = true;
+            self.h_wires.push(h_wire);
+        }
+    }
+
+    fn clear(&mut self) {
+        for h_wire in self.h_wires.iter() {
+            self.is_changed[*h_wire] = false;
+        }
+        self.h_wires.clear();
+    }
 }
 
 impl SimState {
@@ -91,7 +166,25 @@ impl SimState {
         }
 
         for i in 0..h_wires.len() {
-            self.wires[state as usize][h_wires[i]] = logics[i].into();
+            self.set_wire(state, h_wires[i], logics[i].into());
+        }
+    }
+
+    fn set_wire(&mut self, state: StateRef, h_wire: HWire, logic: Logic) {
+        // a forced wire ignores its driver
+        let logic = match &self.forced_wires {
+            Some(forced_wires) => forced_wires[h_wire].unwrap_or(logic),
+            None => logic,
+        };
+
+        let wire = &mut self.wires[state as usize][h_wire];
+        if *wire == logic {
+            return;
+        }
+        *wire = logic;
+
+        if let (StateRef::Cur, Some(changed_wires)) = (state, &mut self.changed_wires) {
+            changed_wires.insert(h_wire);
         }
     }
 
@@ -112,25 +205,429 @@ impl SimState {
 
 impl<'m> Sim<'m> {
     pub fn new(module: &'m Module) -> Self {
-        let num_wires: usize = compute_num_wires(module);
+        Self::with_config(module, SimConfig::default())
+    }
 
-        let wire_nodes = compute_wire_graph(module, num_wires);
-        let cell_nodes = compute_cell_graph(module, &wire_nodes);
-        let update_order = compute_cell_update_order(module, &wire_nodes, &cell_nodes);
+    /// # Panics
+    ///
+    /// if [Design::try_with_config] fails
+    pub fn with_config(module: &'m Module, config: SimConfig) -> Self {
+        Self::from_design(Arc::new(Design::with_config(module, config)))
+    }
+
+    /// like [Sim::with_config], but returns the errors of [Design::try_with_config]
+    pub fn try_with_config(module: &'m Module, config: SimConfig) -> Result<Self, SimError> {
+        Ok(Self::from_design(Arc::new(Design::try_with_config(
+            module, config,
+        )?)))
+    }
+
+    /// only allocates the wires, the schedule is shared with `design`
+    pub fn from_design(design: Arc<Design<'m>>) -> Self {
+        let changed_wires = match (design.config.engine, design.config.scheduler) {
+            (Engine::Interpreter, Scheduler::EventDriven) => {
+                Some(ChangedWires::new(design.num_wires))
+            }
+            _ => None,
+        };
+
+        let partition_sim_states = match &design.partitions {
+            Some(partitions) => partitions
+                .new_sim_states(|tape| new_sim_state(design.num_wires, tape, design.ops, None)),
+            None => Vec::new(),
+        };
 
-        Sim {
+        let sim_state = new_sim_state(
+            design.num_wires,
+            design.tape.as_ref(),
+            design.ops,
+            changed_wires,
+        );
+
+        let mut sim = Sim {
             frame: 0,
-            module,
-            update_order,
-            sim_state: SimState {
-                wires: [vec![Logic::X; num_wires], vec![Logic::X; num_wires]],
-                set_wires_deferred: Vec::new(),
-                ops: &OP_FNS,
-            },
+            time: 0,
+            clocks: Vec::new(),
+            event_queue: EventQueue::new(design.update_order.len()),
+            needs_full_sweep: true,
+            partition_sim_states,
+            history: None,
+            watches: Vec::new(),
+            watch_hits: Vec::new(),
+            formal: FormalState::new(design.module.formal_cells.len()),
+            sim_state,
+            design,
+        };
+        sim.reset();
+        sim
+    }
+
+    pub fn design(&self) -> &Arc<Design<'m>> {
+        &self.design
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Drives `port` with `clock` from now on, starting with its value at [Sim::time].
+    /// The history starts over.
+    pub fn add_clock(&mut self, port: &ModuleInPort<1>, clock: Clock) -> Result<(), SimError> {
+        if clock.period < 2 || !(0.0..=1.0).contains(&clock.duty_cycle) {
+            return Err(SimError::IllegalClock {
+                port: port.name.clone(),
+                clock,
+            });
         }
+
+        let h_wire = port.wires[0];
+        self.clocks.retain(|it| it.h_wire != h_wire);
+        self.clocks.push(ClockSource { clock, h_wire });
+        self.init_clocks();
+        self.reset_history();
+        Ok(())
+    }
+
+    /// Advances the time by `duration` and simulates one frame at each time a clock changes.
+    /// Edges of several clocks at the same time are simulated in the same frame.
+    /// Stops right after the frame in which a [Watchpoint] fired or, with
+    /// [SimConfig::stop_on_formal_failure], a formal cell failed.
+    pub fn run_for(&mut self, duration: u64) -> Result<Option<Halt>, SimError> {
+        let end = self.time + duration;
+        while let Some(time) = self.next_clock_edge()
+            && time <= end
+        {
+            self.step_clocks(time)?;
+            if let Some(halt) = self.halt() {
+                return Ok(Some(halt));
+            }
+        }
+        self.time = end;
+        Ok(None)
+    }
+
+    /// Runs until the clock driving `port` has risen `num_cycles` times.
+    /// Stops right after the frame in which a [Watchpoint] fired or, with
+    /// [SimConfig::stop_on_formal_failure], a formal cell failed.
+    pub fn run_cycles(
+        &mut self,
+        port: &ModuleInPort<1>,
+        num_cycles: usize,
+    ) -> Result<Option<Halt>, SimError> {
+        let h_wire = port.wires[0];
+        if !self.clocks.iter().any(|it| it.h_wire == h_wire) {
+            return Err(SimError::NotFound {
+                label: "clock",
+                name: port.name.clone(),
+            });
+        }
+
+        let mut num_rising_edges = 0;
+        while num_rising_edges < num_cycles {
+            let time = self.next_clock_edge().unwrap();
+            let before = self.sim_state.wires[StateRef::Cur as usize][h_wire];
+            self.step_clocks(time)?;
+            let after = self.sim_state.wires[StateRef::Cur as usize][h_wire];
+            if Edge::of(before, after) == Edge::POSITIVE {
+                num_rising_edges += 1;
+            }
+            if let Some(halt) = self.halt() {
+                return Ok(Some(halt));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Simulates up to `max_frames` frames, until `predicate` holds after a frame, a
+    /// [Watchpoint] fires or, with [SimConfig::stop_on_formal_failure], a formal cell fails.
+    /// Each frame advances the time to the next clock edge, if there are clocks, otherwise
+    /// the in ports keep their values.
+    pub fn run_until(
+        &mut self,
+        mut predicate: impl FnMut(&mut Sim<'m>) -> bool,
+        max_frames: usize,
+    ) -> Result<Halt, SimError> {
+        for _ in 0..max_frames {
+            match self.next_clock_edge() {
+                Some(time) => self.step_clocks(time)?,
+                None => self.simulate()?,
+            }
+
+            if let Some(halt) = self.halt() {
+                return Ok(halt);
+            }
+            if predicate(self) {
+                return Ok(Halt {
+                    frame: self.frame,
+                    reason: HaltReason::Predicate,
+                });
+            }
+        }
+
+        Ok(Halt {
+            frame: self.frame,
+            reason: HaltReason::MaxFrames,
+        })
+    }
+
+    /// Checks `watchpoint` after every frame from now on and returns its id.
+    pub fn watch(&mut self, watchpoint: Watchpoint) -> Result<usize, SimError> {
+        let width = match &watchpoint {
+            Watchpoint::Changes { .. } => None,
+            Watchpoint::Equals { value, .. } => Some(value.len()),
+        };
+        let wires = self.find_wires(watchpoint.net(), width)?;
+
+        let watch_state = WatchState::new(watchpoint, wires, &self.sim_state);
+        match self.watches.iter().position(|it| it.is_none()) {
+            Some(id) => {
+                self.watches[id] = Some(watch_state);
+                Ok(id)
+            }
+            None => {
+                self.watches.push(Some(watch_state));
+                Ok(self.watches.len() - 1)
+            }
+        }
+    }
+
+    /// removes the watchpoint with the given id
+    pub fn unwatch(&mut self, id: usize) -> Result<Watchpoint, SimError> {
+        self.watches
+            .get_mut(id)
+            .and_then(|it| it.take())
+            .map(|it| it.watchpoint)
+            .ok_or_else(|| SimError::NotFound {
+                label: "watchpoint",
+                name: id.to_string(),
+            })
+    }
+
+    /// the watchpoints by their id
+    pub fn watchpoints(&self) -> impl Iterator<Item = (usize, &Watchpoint)> {
+        self.watches
+            .iter()
+            .enumerate()
+            .filter_map(|(id, it)| it.as_ref().map(|it| (id, &it.watchpoint)))
+    }
+
+    /// the watchpoints that fired in the last frame
+    pub fn watch_hits(&self) -> &[WatchHit] {
+        &self.watch_hits
+    }
+
+    fn check_watches(&mut self) {
+        self.watch_hits.clear();
+        for (id, watch_state) in self.watches.iter_mut().enumerate() {
+            if let Some(watch_state) = watch_state
+                && watch_state.check(&self.sim_state)
+            {
+                self.watch_hits.push(WatchHit {
+                    id,
+                    watchpoint: watch_state.watchpoint.clone(),
+                    value: watch_state.value.clone(),
+                });
+            }
+        }
+    }
+
+    /// the values of the watched nets change without a frame, so none of them fires
+    fn update_watches(&mut self) {
+        self.watch_hits.clear();
+        for watch_state in self.watches.iter_mut().flatten() {
+            watch_state.update(&self.sim_state);
+        }
+    }
+
+    /// the formal cells of the module, sorted by name
+    pub fn formal_cells(&self) -> &'m [FormalCell] {
+        &self.design.module.formal_cells
+    }
+
+    /// The `$assert` and `$assume` cells that failed since the last [Sim::reset], in the order
+    /// they failed. Going back with [Sim::goto_frame] or [Sim::restore] does not take them back.
+    pub fn formal_failures(&self) -> &[FormalEvent] {
+        &self.formal.failures
+    }
+
+    /// How often each of [Sim::formal_cells] fired since the last [Sim::reset]: the number of
+    /// failures of an `$assert` or `$assume` cell, or the number of frames in which the other
+    /// cells held.
+    pub fn formal_counts(&self) -> impl Iterator<Item = (&'m FormalCell, usize)> + '_ {
+        self.formal_cells()
+            .iter()
+            .zip(self.formal.counts.iter().cloned())
+    }
+
+    /// the number of frames in which the `$cover` cell `name` held, see [Sim::formal_counts]
+    pub fn cover_count(&self, name: &str) -> Result<usize, SimError> {
+        self.formal_counts()
+            .find(|(cell, _)| cell.name == name && cell.kind == FormalKind::Cover)
+            .map(|(_, count)| count)
+            .ok_or_else(|| SimError::NotFound {
+                label: "cover cell",
+                name: name.to_string(),
+            })
+    }
+
+    /// the halt after the last frame, formal failures take precedence over watchpoints
+    fn halt(&self) -> Option<Halt> {
+        if self.design.config.stop_on_formal_failure && self.formal.num_new_failures > 0 {
+            let failures = &self.formal.failures;
+            return Some(Halt {
+                frame: self.frame,
+                reason: HaltReason::FormalFailures(
+                    failures[failures.len() - self.formal.num_new_failures..].to_vec(),
+                ),
+            });
+        }
+        (!self.watch_hits.is_empty()).then(|| Halt {
+            frame: self.frame,
+            reason: HaltReason::Watchpoints(self.watch_hits.clone()),
+        })
+    }
+
+    /// the clocks have been at their value of the current time forever, so the first frame
+    /// does not see an edge from `X`
+    fn init_clocks(&mut self) {
+        for clock in self.clocks.iter() {
+            let logic = clock.clock.value_at(self.time);
+            self.sim_state.set_wire(StateRef::Prev, clock.h_wire, logic);
+            self.sim_state.set_wire(StateRef::Cur, clock.h_wire, logic);
+        }
+        self.sync_partition_sim_states();
+    }
+
+    fn next_clock_edge(&self) -> Option<u64> {
+        self.clocks
+            .iter()
+            .map(|it| it.clock.next_edge_after(self.time))
+            .min()
+    }
+
+    fn step_clocks(&mut self, time: u64) -> Result<(), SimError> {
+        self.time = time;
+        for clock in self.clocks.iter() {
+            self.sim_state
+                .set_wire(StateRef::Cur, clock.h_wire, clock.clock.value_at(time));
+        }
+        self.simulate()
+    }
+
+    /// the number of partitions simulated in parallel, `1` if the simulation is serial
+    pub fn num_partitions(&self) -> usize {
+        self.design.num_partitions()
     }
 
     pub fn simulate(&mut self) -> Result<(), SimError> {
+        if let Some(history) = &mut self.history {
+            let inputs = history
+                .h_in_wires
+                .iter()
+                .map(|h_wire| self.sim_state.wires[StateRef::Cur as usize][*h_wire])
+                .collect();
+            history.record(self.frame, self.time, inputs);
+        }
+
+        self.simulate_frame();
+        self.check_watches();
+        self.formal.check(
+            &self.design.module.formal_cells,
+            self.frame,
+            self.time,
+            &self.sim_state,
+        );
+
+        if self
+            .history
+            .as_ref()
+            .is_some_and(|it| it.is_checkpoint(self.frame))
+        {
+            let checkpoint = self.snapshot();
+            self.history.as_mut().unwrap().checkpoint(checkpoint);
+        }
+
+        Ok(())
+    }
+
+    /// the frames [Sim::goto_frame] can go to, `None` if [SimConfig::history] is not set
+    pub fn history_frames(&self) -> Option<RangeInclusive<usize>> {
+        self.history.as_ref().map(|it| it.frames())
+    }
+
+    /// goes back to the state after frame `self.frame() - num_frames`
+    pub fn step_back(&mut self, num_frames: usize) -> Result<(), SimError> {
+        self.goto_frame(self.frame.saturating_sub(num_frames))
+    }
+
+    /// Goes to the state after `frame` by replaying the recorded in ports from the nearest
+    /// checkpoint. Frames after the current one can be reached as well, as long as they were
+    /// simulated before and the simulation did not continue differently since.
+    pub fn goto_frame(&mut self, frame: usize) -> Result<(), SimError> {
+        let Some(history) = self.history.take() else {
+            return Err(SimError::HistoryDisabled);
+        };
+
+        let frames = history.frames();
+        if !frames.contains(&frame) {
+            self.history = Some(history);
+            return Err(SimError::FrameNotInHistory {
+                frame,
+                first_frame: *frames.start(),
+                last_frame: *frames.end(),
+            });
+        }
+
+        let checkpoint = history.nearest_checkpoint(frame);
+        self.restore_state(checkpoint);
+        for frame in checkpoint.frame..frame {
+            let (time, inputs) = history.inputs(frame);
+            self.time = time;
+            self.sim_state
+                .set_wires(StateRef::Cur, &history.h_in_wires, inputs);
+            self.simulate_frame();
+        }
+        self.update_watches();
+        self.formal
+            .update(&self.design.module.formal_cells, &self.sim_state);
+
+        self.history = Some(history);
+        Ok(())
+    }
+
+    fn reset_history(&mut self) {
+        self.history = self.design.config.history.map(|config| {
+            let h_in_wires = self
+                .design
+                .module
+                .in_ports
+                .iter()
+                .flat_map(|it| it.wires.iter().cloned())
+                .collect();
+            HistoryLog::new(config, h_in_wires, self.snapshot())
+        });
+    }
+
+    fn simulate_frame(&mut self) {
+        // only the interpreter writes each wire through SimState::set_wire, where forces apply
+        let is_forced = self.sim_state.forced_wires.is_some();
+
+        if let Some(partitions) = &self.design.partitions
+            && !is_forced
+        {
+            partitions.simulate(
+                self.design.module,
+                &mut self.partition_sim_states,
+                &mut self.sim_state,
+            );
+            self.frame += 1;
+            return;
+        }
+
         #[allow(unused)]
         let mut sub_frame: usize = 0;
         loop {
@@ -141,34 +638,329 @@ impl<'m> Sim<'m> {
                 println!("wires: {:?}", self.sim_state.wires[StateRef::Cur as usize]);
             }
 
-            for h_cell in self.update_order.iter() {
-                let cell = &self.module.cells[*h_cell];
-
-                cell.simulate(&mut self.sim_state);
-            }
-
-            unsafe {
-                let wires = self.sim_state.wires.as_mut_slice() as *mut [Vec<Logic>];
-                (*wires)[StateRef::Prev as usize]
-                    .copy_from_slice(&(*wires)[StateRef::Cur as usize]);
+            match (self.design.config.engine, self.design.config.scheduler) {
+                (Engine::Interpreter, Scheduler::EventDriven) => self.sweep_changed_cells(),
+                _ if is_forced => self.sweep_all_cells(),
+                (Engine::Interpreter, Scheduler::FullSweep) => self.sweep_all_cells(),
+                (Engine::Tape, _) => self.sweep_tape(),
+                #[cfg(feature = "jit")]
+                (Engine::Jit, _) => self.sweep_jit(),
+                // a design with this engine cannot be built without the feature
+                #[cfg(not(feature = "jit"))]
+                (Engine::Jit, _) => unreachable!(),
             }
 
-            if self.sim_state.set_wires_deferred.len() > 0 {
-                let cur_wires = &mut self.sim_state.wires[StateRef::Cur as usize];
-                for (h_wire, logic) in self.sim_state.set_wires_deferred.iter() {
-                    cur_wires[*h_wire] = *logic;
+            if !self.sim_state.set_wires_deferred.is_empty() {
+                let mut set_wires_deferred = std::mem::take(&mut self.sim_state.set_wires_deferred);
+                for (h_wire, logic) in set_wires_deferred.iter() {
+                    self.sim_state.set_wire(StateRef::Cur, *h_wire, *logic);
                 }
-                self.sim_state.set_wires_deferred.clear();
+                set_wires_deferred.clear();
+                self.sim_state.set_wires_deferred = set_wires_deferred;
                 sub_frame += 1;
             } else {
                 break;
             }
         }
 
+        if is_forced {
+            self.sync_partition_sim_states();
+        }
         self.frame += 1;
+    }
+
+    fn sweep_all_cells(&mut self) {
+        for h_cell in self.design.update_order.iter() {
+            let cell = &self.design.module.cells[*h_cell];
+
+            cell.simulate(&mut self.sim_state);
+        }
+
+        unsafe {
+            let wires = self.sim_state.wires.as_mut_slice() as *mut [Vec<Logic>];
+            (*wires)[StateRef::Prev as usize].copy_from_slice(&(*wires)[StateRef::Cur as usize]);
+        }
+    }
+
+    fn sweep_tape(&mut self) {
+        self.design.tape.as_ref().unwrap().run(&mut self.sim_state);
+
+        let [prev_wires, cur_wires] = &mut self.sim_state.wires;
+        prev_wires.copy_from_slice(cur_wires);
+    }
+
+    #[cfg(feature = "jit")]
+    fn sweep_jit(&mut self) {
+        self.design.jit.as_ref().unwrap().run(&mut self.sim_state);
+
+        let [prev_wires, cur_wires] = &mut self.sim_state.wires;
+        prev_wires.copy_from_slice(cur_wires);
+    }
+
+    /// Evaluates only the cells reading a wire that changed, in the same order as
+    /// [Sim::sweep_all_cells] would. Cells whose inputs did not change would
+    /// reproduce their current outputs, so skipping them yields identical results.
+    fn sweep_changed_cells(&mut self) {
+        if self.needs_full_sweep {
+            for rank in 0..self.design.update_order.len() {
+                self.event_queue.schedule(rank);
+            }
+            self.needs_full_sweep = false;
+        }
+
+        let mut num_scheduled_wires: usize = 0;
+        loop {
+            let changed_wires = self.sim_state.changed_wires.as_ref().unwrap();
+            for h_wire in changed_wires.h_wires[num_scheduled_wires..].iter() {
+                for h_cell in self.design.wire_nodes[*h_wire].h_out_cells.iter() {
+                    if let Some(rank) = self.design.cell_ranks[*h_cell] {
+                        self.event_queue.schedule(rank);
+                    }
+                }
+            }
+            num_scheduled_wires = changed_wires.h_wires.len();
+
+            match self.event_queue.pop() {
+                Some(rank) => {
+                    let cell = &self.design.module.cells[self.design.update_order[rank]];
+
+                    cell.simulate(&mut self.sim_state);
+                }
+                None => break,
+            }
+        }
+
+        // only the changed wires differ between the previous and current state
+        let changed_wires = self.sim_state.changed_wires.as_mut().unwrap();
+        let [prev_wires, cur_wires] = &mut self.sim_state.wires;
+        for h_wire in changed_wires.h_wires.iter() {
+            prev_wires[*h_wire] = cur_wires[*h_wire];
+        }
+        changed_wires.clear();
+    }
+
+    /// a copy of the wires, see [Snapshot]
+    pub fn snapshot(&self) -> Snapshot {
+        let num_wires = self.design.num_wires;
+        let [prev_wires, cur_wires] = &self.sim_state.wires;
+        Snapshot {
+            module: self.design.module.name.clone(),
+            frame: self.frame,
+            time: self.time,
+            prev_wires: prev_wires[..num_wires].to_vec(),
+            cur_wires: cur_wires[..num_wires].to_vec(),
+        }
+    }
+
+    /// Continues the simulation from `snapshot`, as if it had never left that state.
+    /// The history starts over at the frame of the snapshot.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), SimError> {
+        let module = self.design.module;
+        let num_wires = self.design.num_wires;
+        if snapshot.module != module.name
+            || snapshot.prev_wires.len() != num_wires
+            || snapshot.cur_wires.len() != num_wires
+        {
+            return Err(SimError::SnapshotMismatch {
+                module: module.name.clone(),
+                num_wires,
+                snapshot_module: snapshot.module.clone(),
+                snapshot_num_wires: snapshot.cur_wires.len(),
+            });
+        }
+
+        self.restore_state(snapshot);
+        self.reset_history();
+
         Ok(())
     }
 
+    /// Goes back to the power-up state of frame `0` and time `0`: the registers are initialized
+    /// from their `init` attribute or [SimConfig::register_init], all other wires are `X`.
+    /// The clocks stay attached.
+    pub fn reset(&mut self) {
+        let design = self.design.clone();
+        self.restore_wires(0, 0, &design.initial_wires, &design.initial_wires);
+        self.init_clocks();
+        self.reset_history();
+        self.formal.clear();
+        self.formal
+            .update(&design.module.formal_cells, &self.sim_state);
+    }
+
+    fn restore_state(&mut self, snapshot: &Snapshot) {
+        self.restore_wires(
+            snapshot.frame,
+            snapshot.time,
+            &snapshot.prev_wires,
+            &snapshot.cur_wires,
+        );
+    }
+
+    fn restore_wires(&mut self, frame: usize, time: u64, prev: &[Logic], cur: &[Logic]) {
+        let num_wires = self.design.num_wires;
+
+        let [prev_wires, cur_wires] = &mut self.sim_state.wires;
+        prev_wires[..num_wires].copy_from_slice(prev);
+        cur_wires[..num_wires].copy_from_slice(cur);
+        self.sim_state.set_wires_deferred.clear();
+
+        if let Some(forced_wires) = &self.sim_state.forced_wires {
+            for (h_wire, logic) in forced_wires.iter().enumerate() {
+                if let Some(logic) = logic {
+                    cur_wires[h_wire] = *logic;
+                }
+            }
+        }
+
+        if let Some(changed_wires) = &mut self.sim_state.changed_wires {
+            changed_wires.clear();
+            for h_wire in 0..num_wires {
+                if prev_wires[h_wire] != cur_wires[h_wire] {
+                    changed_wires.insert(h_wire);
+                }
+            }
+        }
+
+        self.sync_partition_sim_states();
+        self.needs_full_sweep = true;
+        self.frame = frame;
+        self.time = time;
+        self.update_watches();
+        self.formal
+            .update(&self.design.module.formal_cells, &self.sim_state);
+    }
+
+    /// The partitions only read the wires they drive and the in ports, which are the same
+    /// in their copy as in the merged wires, unless the wires were changed from outside.
+    fn sync_partition_sim_states(&mut self) {
+        let num_wires = self.design.num_wires;
+        let [prev, cur] = &self.sim_state.wires;
+        for sim_state in self.partition_sim_states.iter_mut() {
+            let [prev_wires, cur_wires] = &mut sim_state.wires;
+            prev_wires[..num_wires].copy_from_slice(&prev[..num_wires]);
+            cur_wires[..num_wires].copy_from_slice(&cur[..num_wires]);
+            sim_state.set_wires_deferred.clear();
+        }
+    }
+
+    /// Keeps the net or the outputs of the register with the given name at `logics`,
+    /// regardless of what drives it, until it is released.
+    /// Forcing, releasing and depositing start the history over.
+    pub fn force<E: Copy + Into<Logic>>(
+        &mut self,
+        name: &str,
+        logics: &[E],
+    ) -> Result<(), SimError> {
+        let wires = self.find_wires(name, Some(logics.len()))?;
+        let num_wires = self.design.num_wires;
+
+        let forced_wires = self
+            .sim_state
+            .forced_wires
+            .get_or_insert_with(|| vec![None; num_wires]);
+        for (wire, logic) in wires.iter().zip(logics.iter()) {
+            if let HWireOrLogic::HWire(h_wire) = wire {
+                forced_wires[*h_wire] = Some((*logic).into());
+            }
+        }
+
+        self.deposit_wires(&wires, logics);
+        Ok(())
+    }
+
+    /// Gives a forced net back to its driver. The outputs of a register keep the forced value
+    /// until the register is clocked.
+    pub fn release(&mut self, name: &str) -> Result<(), SimError> {
+        let wires = self.find_wires(name, None)?;
+
+        if let Some(forced_wires) = &mut self.sim_state.forced_wires {
+            for wire in wires.iter() {
+                if let HWireOrLogic::HWire(h_wire) = wire {
+                    forced_wires[*h_wire] = None;
+                }
+            }
+            if forced_wires.iter().all(|it| it.is_none()) {
+                self.sim_state.forced_wires = None;
+            }
+        }
+
+        // the drivers of the released wires have to be evaluated again
+        self.needs_full_sweep = true;
+        self.reset_history();
+        Ok(())
+    }
+
+    /// Sets the net or the outputs of the register with the given name once, after which
+    /// the design continues from there. Has no effect on forced wires.
+    pub fn deposit<E: Copy + Into<Logic>>(
+        &mut self,
+        name: &str,
+        logics: &[E],
+    ) -> Result<(), SimError> {
+        let wires = self.find_wires(name, Some(logics.len()))?;
+        self.deposit_wires(&wires, logics);
+        Ok(())
+    }
+
+    fn deposit_wires<E: Copy + Into<Logic>>(&mut self, wires: &[HWireOrLogic], logics: &[E]) {
+        for (wire, logic) in wires.iter().zip(logics.iter()) {
+            if let HWireOrLogic::HWire(h_wire) = wire {
+                self.sim_state
+                    .set_wire(StateRef::Cur, *h_wire, (*logic).into());
+            }
+        }
+        self.sync_partition_sim_states();
+        self.needs_full_sweep = true;
+        self.update_watches();
+        self.reset_history();
+    }
+
+    /// the value of the net or the outputs of the register with the given name
+    pub fn get_net(&self, name: &str) -> Result<LogicVec, SimError> {
+        let wires = self.find_wires(name, None)?;
+        let mut logics = vec![Logic::X; wires.len()];
+        self.sim_state
+            .get_wires_or_logic(StateRef::Cur, &wires, &mut logics);
+        Ok(LogicVec(logics))
+    }
+
+    pub fn net_width(&self, name: &str) -> Result<usize, SimError> {
+        Ok(self.find_wires(name, None)?.len())
+    }
+
+    /// the wires of the net with the given name, otherwise the outputs of the register
+    fn find_wires(&self, name: &str, width: Option<usize>) -> Result<Vec4<HWireOrLogic>, SimError> {
+        let module = self.design.module;
+
+        let wires: Vec4<HWireOrLogic> = match module.nets.iter().find(|it| it.name == name) {
+            Some(net) => net.wires.clone(),
+            None => module
+                .cells
+                .iter()
+                .find(|it| it.is_sequential() && it.name() == name)
+                .map(|cell| {
+                    CellWires::get_out_port_h_wires(cell)
+                        .map(HWireOrLogic::HWire)
+                        .collect()
+                })
+                .ok_or_else(|| SimError::NotFound {
+                    label: "net",
+                    name: name.to_string(),
+                })?,
+        };
+
+        match width {
+            Some(width) if width != wires.len() => Err(SimError::NetWidthMismatch {
+                module: module.name.clone(),
+                net: name.to_string(),
+                expected: wires.len(),
+                actual: width,
+            }),
+            _ => Ok(wires),
+        }
+    }
+
     pub fn set<E: Copy + Into<Logic>, const L: usize>(
         &mut self,
         port: &ModuleInPort<L>,
@@ -179,8 +971,7 @@ impl<'m> Sim<'m> {
     }
 
     pub fn set_dynamic<E: Copy + Into<Logic>>(&mut self, port: &ModuleInPort, logics: &[E]) {
-        self.sim_state
-            .set_wires(StateRef::Cur, &port.wires, &logics);
+        self.sim_state.set_wires(StateRef::Cur, &port.wires, logics);
     }
 
     pub fn get<const L: usize>(&mut self, port: &ModuleOutPort<L>) -> [Logic; L] {
@@ -196,9 +987,177 @@ impl<'m> Sim<'m> {
             .get_wires(StateRef::Cur, &port.wires, &mut logics);
         logics
     }
+
+    /// the in or out port with the given name, whatever its width
+    pub fn port(&self, name: &str) -> Result<PortHandle, SimError> {
+        let module = self.design.module;
+        let info = module.port_info(name)?.clone();
+        let wires = match info.direction {
+            PortDirection::Input => {
+                &module
+                    .get_port_dynamic(&module.in_ports, name, info.width)?
+                    .wires
+            }
+            PortDirection::Output => {
+                &module
+                    .get_port_dynamic(&module.out_ports, name, info.width)?
+                    .wires
+            }
+        };
+        Ok(PortHandle {
+            module: module.name.clone(),
+            wires: wires.clone(),
+            info,
+        })
+    }
+
+    /// fails if `port` is not an in port or `logics` does not have its width
+    pub fn set_port<E: Copy + Into<Logic>>(
+        &mut self,
+        port: &PortHandle,
+        logics: &[E],
+    ) -> Result<(), SimError> {
+        port.check_direction(PortDirection::Input)?;
+        if logics.len() != port.width() {
+            return Err(SimError::PortWidthMismatch {
+                module: port.module.clone(),
+                cell: None,
+                port: port.name().to_string(),
+                expected: port.width(),
+                actual: logics.len(),
+            });
+        }
+        self.sim_state.set_wires(StateRef::Cur, &port.wires, logics);
+        Ok(())
+    }
+
+    /// the value of an in or out port
+    pub fn get_port(&mut self, port: &PortHandle) -> LogicVec {
+        let mut logics = vec![Logic::X; port.width()];
+        self.sim_state
+            .get_wires(StateRef::Cur, &port.wires, &mut logics);
+        LogicVec(logics)
+    }
+
+    /// fails if `value` does not have the width of `port`
+    pub fn set_vec<const L: usize>(
+        &mut self,
+        port: &ModuleInPort<L>,
+        value: &LogicVec,
+    ) -> Result<(), SimError> {
+        if value.len() != port.wires.len() {
+            return Err(SimError::PortWidthMismatch {
+                module: self.design.module.name.clone(),
+                cell: None,
+                port: port.name.clone(),
+                expected: port.wires.len(),
+                actual: value.len(),
+            });
+        }
+        self.sim_state.set_wires(StateRef::Cur, &port.wires, value);
+        Ok(())
+    }
+
+    pub fn get_vec<const L: usize>(&mut self, port: &ModuleOutPort<L>) -> LogicVec {
+        let mut logics = vec![Logic::X; port.wires.len()];
+        self.sim_state
+            .get_wires(StateRef::Cur, &port.wires, &mut logics);
+        LogicVec(logics)
+    }
+
+    /// truncated or zero extended to the width of `port`
+    pub fn set_u64<const L: usize>(&mut self, port: &ModuleInPort<L>, value: u64) {
+        self.set_big(port, &[value]);
+    }
+
+    /// truncated or sign extended to the width of `port`
+    pub fn set_i64<const L: usize>(&mut self, port: &ModuleInPort<L>, value: i64) {
+        self.set_big_signed(port, &[value as u64]);
+    }
+
+    /// `words` LSB first, truncated or zero extended to the width of `port`
+    pub fn set_big<const L: usize>(&mut self, port: &ModuleInPort<L>, words: &[u64]) {
+        let logics = words_to_logics(words, false, port.wires.len());
+        self.sim_state
+            .set_wires(StateRef::Cur, &port.wires, &logics);
+    }
+
+    /// `words` LSB first, truncated or sign extended to the width of `port`
+    pub fn set_big_signed<const L: usize>(&mut self, port: &ModuleInPort<L>, words: &[u64]) {
+        let logics = words_to_logics(words, true, port.wires.len());
+        self.sim_state
+            .set_wires(StateRef::Cur, &port.wires, &logics);
+    }
+
+    /// fails if `port` is wider than 64 bits
+    pub fn get_u64<const L: usize>(
+        &mut self,
+        port: &ModuleOutPort<L>,
+    ) -> Result<IntValue<u64>, SimError> {
+        self.check_int_width(port, 64)?;
+        Ok(self.get_big(port).map(|it| it[0]))
+    }
+
+    /// sign extended from the MSB of `port`, fails if `port` is wider than 64 bits
+    pub fn get_i64<const L: usize>(
+        &mut self,
+        port: &ModuleOutPort<L>,
+    ) -> Result<IntValue<i64>, SimError> {
+        self.check_int_width(port, 64)?;
+        let width = port.wires.len();
+        Ok(self.get_big(port).map(|it| sign_extend(it[0], width)))
+    }
+
+    /// the words LSB first, at least one
+    pub fn get_big<const L: usize>(&mut self, port: &ModuleOutPort<L>) -> IntValue<Vec<u64>> {
+        let mut logics = vec![Logic::X; port.wires.len()];
+        self.sim_state
+            .get_wires(StateRef::Cur, &port.wires, &mut logics);
+        logics_to_words(&logics)
+    }
+
+    fn check_int_width<const L: usize>(
+        &self,
+        port: &ModuleOutPort<L>,
+        max_width: usize,
+    ) -> Result<(), SimError> {
+        match port.wires.len() > max_width {
+            true => Err(SimError::PortTooWide {
+                module: self.design.module.name.clone(),
+                port: port.name.clone(),
+                width: port.wires.len(),
+                max_width,
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+pub(super) fn new_sim_state(
+    num_wires: usize,
+    tape: Option<&Tape>,
+    ops: &'static OpFns,
+    changed_wires: Option<ChangedWires>,
+) -> SimState {
+    // the tape keeps its constants and temporaries behind the wires of the module
+    let num_slots = tape.map_or(num_wires, |tape| tape.num_slots);
+    let mut wires = [vec![Logic::X; num_slots], vec![Logic::X; num_slots]];
+    if let Some(tape) = tape {
+        for wires in wires.iter_mut() {
+            tape.init_slots(wires);
+        }
+    }
+
+    SimState {
+        wires,
+        set_wires_deferred: Vec::new(),
+        ops,
+        changed_wires,
+        forced_wires: None,
+    }
 }
 
-fn compute_num_wires(module: &Module) -> usize {
+pub(super) fn compute_num_wires(module: &Module) -> usize {
     let mut num_wires: usize = 0;
 
     for cell in module.cells.iter() {
@@ -207,16 +1166,38 @@ fn compute_num_wires(module: &Module) -> usize {
         }
     }
 
+    for h_wire in module.formal_cells.iter().flat_map(|cell| {
+        [&cell.port_a, &cell.port_en]
+            .into_iter()
+            .chain(cell.port_trg.iter())
+            .flat_map(|port| port.wires.iter())
+            .filter_map(|wire| match wire {
+                HWireOrLogic::HWire(h_wire) => Some(*h_wire),
+                HWireOrLogic::Logic(_) => None,
+            })
+    }) {
+        num_wires = usize::max(num_wires, h_wire + 1);
+    }
+
+    for h_wire in module
+        .in_ports
+        .iter()
+        .flat_map(|it| it.wires.iter())
+        .chain(module.out_ports.iter().flat_map(|it| it.wires.iter()))
+    {
+        num_wires = usize::max(num_wires, h_wire + 1);
+    }
+
     num_wires
 }
 
 #[derive(Clone, Debug)]
-struct WireNode {
+pub(super) struct WireNode {
     pub h_in_cell: Option<HCell>,
     pub h_out_cells: Set4<HCell>,
 }
 
-fn compute_wire_graph(module: &Module, num_wires: usize) -> Vec<WireNode> {
+pub(super) fn compute_wire_graph(module: &Module, num_wires: usize) -> Vec<WireNode> {
     let mut wire_nodes: Vec<WireNode> = vec![
         WireNode {
             h_in_cell: None,
@@ -243,13 +1224,13 @@ fn compute_wire_graph(module: &Module, num_wires: usize) -> Vec<WireNode> {
 
 #[allow(unused)]
 #[derive(Clone, Debug)]
-struct CellNode {
+pub(super) struct CellNode {
     pub name: String,
     pub h_prev_cells: Set4<HCell>,
     pub h_next_cells: Set4<HCell>,
 }
 
-fn compute_cell_graph(module: &Module, wire_nodes: &Vec<WireNode>) -> Vec<CellNode> {
+pub(super) fn compute_cell_graph(module: &Module, wire_nodes: &[WireNode]) -> Vec<CellNode> {
     let mut cell_nodes: Vec<CellNode> = module
         .cells
         .iter()
@@ -261,67 +1242,49 @@ fn compute_cell_graph(module: &Module, wire_nodes: &Vec<WireNode>) -> Vec<CellNo
         .collect();
 
     for wire_node in wire_nodes.iter() {
-        match wire_node.h_in_cell {
-            Some(h_in_cell) => {
-                for h_out_cell in wire_node.h_out_cells.iter() {
-                    cell_nodes[h_in_cell].h_next_cells.insert(*h_out_cell);
-                    cell_nodes[*h_out_cell].h_prev_cells.insert(h_in_cell);
-                }
+        if let Some(h_in_cell) = wire_node.h_in_cell {
+            for h_out_cell in wire_node.h_out_cells.iter() {
+                cell_nodes[h_in_cell].h_next_cells.insert(*h_out_cell);
+                cell_nodes[*h_out_cell].h_prev_cells.insert(h_in_cell);
             }
-            None => (),
         }
     }
 
     cell_nodes
 }
 
-fn compute_cell_update_order(
-    module: &Module,
-    wire_nodes: &Vec<WireNode>,
-    cell_nodes: &Vec<CellNode>,
-) -> Vec<HCell> {
-    let mut token_nodes: Vec<i32> = cell_nodes
+pub(super) fn compute_cell_update_order(module: &Module, cell_nodes: &[CellNode]) -> Vec<HCell> {
+    // the outputs of sequential cells are deferred to the next sub-frame,
+    // so the edges leaving them do not constrain the update order
+    let mut token_nodes: Vec<usize> = cell_nodes
         .iter()
-        .map(|it| it.h_prev_cells.len() as i32)
+        .map(|it| {
+            it.h_prev_cells
+                .iter()
+                .filter(|h_prev_cell| !module.cells[**h_prev_cell].is_sequential())
+                .count()
+        })
         .collect();
 
-    let mut queue: VecDeque<HCell> = VecDeque::new();
-    for h_cell in compute_input_cells(module, wire_nodes).iter() {
-        queue.push_back(*h_cell);
-        token_nodes[*h_cell] += 1;
-    }
+    let mut queue: VecDeque<HCell> = (0..cell_nodes.len())
+        .filter(|h_cell| token_nodes[*h_cell] == 0)
+        .collect();
 
     let mut update_order: Vec<HCell> = Vec::new();
     while let Some(h_cur_cell) = queue.pop_front() {
-        token_nodes[h_cur_cell] -= 1;
+        update_order.push(h_cur_cell);
 
-        match token_nodes[h_cur_cell] {
-            i32::MIN..=-1 => {
-                panic!("illegal state")
-            }
-            0 => {
-                update_order.push(h_cur_cell);
-                for h_next_cell in cell_nodes[h_cur_cell].h_next_cells.iter() {
-                    queue.push_back(*h_next_cell);
-                }
-            }
-            _ => (),
+        if module.cells[h_cur_cell].is_sequential() {
+            continue;
         }
-    }
 
-    update_order
-}
-
-fn compute_input_cells(module: &Module, wire_nodes: &Vec<WireNode>) -> Set4<HCell> {
-    let mut input_cells: Set4<HWire> = Set4::new();
-
-    for in_port in module.in_ports.iter() {
-        for h_wire in in_port.wires.iter() {
-            //
-            for h_out_cell in wire_nodes[*h_wire].h_out_cells.iter() {
-                input_cells.insert(*h_out_cell);
+        for h_next_cell in cell_nodes[h_cur_cell].h_next_cells.iter() {
+            token_nodes[*h_next_cell] -= 1;
+            if token_nodes[*h_next_cell] == 0 {
+                queue.push_back(*h_next_cell);
             }
         }
     }
-    input_cells
+
+    update_order
 }
@@ -0,0 +1,72 @@
+use crate::common::SimError;
+use crate::sim::Logic;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+
+/// The complete state of a [crate::sim::Sim] between two frames.
+///
+/// Cells keep all of their state in wires, so the wires of the module, the frame and the time
+/// of the [crate::sim::Clock]s are all there is. Deferred wires are always applied before a
+/// frame ends and never part of a snapshot.
+/// A snapshot does not depend on the [crate::sim::SimConfig], it can be restored into any
+/// [crate::sim::Sim] of the same module.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub module: String,
+    pub frame: usize,
+    #[serde(default)]
+    pub time: u64,
+    #[serde(with = "logics")]
+    pub prev_wires: Vec<Logic>,
+    #[serde(with = "logics")]
+    pub cur_wires: Vec<Logic>,
+}
+
+impl Snapshot {
+    pub fn write_to_file(&self, file_name: &str) -> Result<(), SimError> {
+        serde_json::to_writer(BufWriter::new(File::create(file_name)?), self)?;
+        Ok(())
+    }
+
+    pub fn read_from_file(file_name: &str) -> Result<Snapshot, SimError> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(
+            file_name,
+        )?))?)
+    }
+}
+
+/// the wires as a string of `0`, `1` and `x`, one character per wire
+mod logics {
+    use crate::sim::Logic;
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(logics: &[Logic], serializer: S) -> Result<S::Ok, S::Error> {
+        let string: String = logics
+            .iter()
+            .map(|logic| match logic {
+                Logic::_0 => '0',
+                Logic::_1 => '1',
+                Logic::X => 'x',
+            })
+            .collect();
+        serializer.serialize_str(&string)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Logic>, D::Error> {
+        String::deserialize(deserializer)?
+            .chars()
+            .map(|char| match char {
+                '0' => Ok(Logic::_0),
+                '1' => Ok(Logic::_1),
+                'x' => Ok(Logic::X),
+                _ => Err(D::Error::custom(format!("illegal logic [{}]", char))),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,155 @@
+use crate::model::HCell;
+use crate::model::HWire;
+use crate::model::HWireOrLogic;
+use crate::model::Module;
+use crate::ops::BinaryOp;
+use crate::ops::TernaryOp;
+use crate::ops::UnaryOp;
+use crate::sim::CellSimModel;
+use crate::sim::Edge;
+use crate::sim::Logic;
+use crate::sim::Logic_Len;
+use crate::sim::Logic_Variants;
+use crate::sim::SimState;
+
+/// An index into the wires of a [SimState]. The first slots are the wires of the
+/// module, followed by one slot per constant [Logic] value and the temporaries of
+/// lowered cells.
+pub type Slot = u32;
+
+/// A single bit operation of a [Tape].
+#[derive(Copy, Clone, Debug)]
+pub enum Instr {
+    Unary {
+        op: UnaryOp,
+        a: Slot,
+        y: Slot,
+    },
+    Binary {
+        op: BinaryOp,
+        a: Slot,
+        b: Slot,
+        y: Slot,
+    },
+    Ternary {
+        op: TernaryOp,
+        a: Slot,
+        b: Slot,
+        c: Slot,
+        y: Slot,
+    },
+    DFlipFlop {
+        polarity: Edge,
+        clk: Slot,
+        d: Slot,
+        q: Slot,
+    },
+}
+
+/// The update order of a module lowered to a flat list of bit operations.
+pub struct Tape {
+    pub instrs: Vec<Instr>,
+    pub num_wires: usize,
+    pub num_slots: usize,
+}
+
+pub struct TapeBuilder {
+    instrs: Vec<Instr>,
+    num_wires: usize,
+    num_slots: usize,
+}
+
+impl TapeBuilder {
+    pub fn slot(&self, wire: &HWireOrLogic) -> Slot {
+        match wire {
+            HWireOrLogic::HWire(h_wire) => self.wire_slot(*h_wire),
+            HWireOrLogic::Logic(logic) => (self.num_wires + *logic as usize) as Slot,
+        }
+    }
+
+    pub fn wire_slot(&self, h_wire: HWire) -> Slot {
+        h_wire as Slot
+    }
+
+    pub fn const_slot(&self, logic: Logic) -> Slot {
+        (self.num_wires + logic as usize) as Slot
+    }
+
+    /// allocates a slot that is not visible outside of the tape
+    pub fn temp_slot(&mut self) -> Slot {
+        self.num_slots += 1;
+        (self.num_slots - 1) as Slot
+    }
+
+    pub fn push(&mut self, instr: Instr) {
+        self.instrs.push(instr);
+    }
+}
+
+impl Tape {
+    pub fn compile(module: &Module, update_order: &[HCell], num_wires: usize) -> Tape {
+        let mut builder = TapeBuilder {
+            instrs: Vec::new(),
+            num_wires,
+            num_slots: num_wires + Logic_Len,
+        };
+
+        for h_cell in update_order.iter() {
+            module.cells[*h_cell].compile(&mut builder);
+        }
+
+        Tape {
+            instrs: builder.instrs,
+            num_wires,
+            num_slots: builder.num_slots,
+        }
+    }
+
+    /// the slots holding the constant [Logic] values, which have to be initialized once
+    pub fn init_slots(&self, wires: &mut [Logic]) {
+        for logic in Logic_Variants {
+            wires[self.num_wires + logic as usize] = logic;
+        }
+    }
+
+    pub fn run(&self, sim: &mut SimState) {
+        let unary = &sim.ops.unary;
+        let binary = &sim.ops.binary;
+        let ternary = &sim.ops.ternary;
+        let [prev, cur] = &mut sim.wires;
+        let set_wires_deferred = &mut sim.set_wires_deferred;
+
+        // every slot of the tape is below num_slots, which allows unchecked access
+        assert!(prev.len() >= self.num_slots && cur.len() >= self.num_slots);
+        let prev = prev.as_ptr();
+        let cur = cur.as_mut_ptr();
+
+        unsafe {
+            let get = |slot: Slot| *cur.add(slot as usize);
+
+            for instr in self.instrs.iter() {
+                match *instr {
+                    Instr::Unary { op, a, y } => {
+                        *cur.add(y as usize) = unary[op][get(a)];
+                    }
+                    Instr::Binary { op, a, b, y } => {
+                        *cur.add(y as usize) = binary[op][(get(a), get(b))];
+                    }
+                    Instr::Ternary { op, a, b, c, y } => {
+                        *cur.add(y as usize) = ternary[op][(get(a), get(b), get(c))];
+                    }
+                    Instr::DFlipFlop {
+                        polarity,
+                        clk,
+                        d,
+                        q,
+                    } => {
+                        if Edge::of(*prev.add(clk as usize), get(clk)) == polarity {
+                            set_wires_deferred.push((q as HWire, get(d)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
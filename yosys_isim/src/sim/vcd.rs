@@ -0,0 +1,164 @@
+use crate::common::SimError;
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+use crate::sim::Sim;
+use std::io::Write;
+
+/// What the timestamps of a [VcdWriter] count.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VcdTime {
+    /// [Sim::time], as advanced by the clocks
+    Time,
+    /// [Sim::frame], for simulations without clocks
+    Frame,
+}
+
+/// Writes the named nets of a [Sim] as a Value Change Dump, one sample per call of
+/// [VcdWriter::sample]. Nets whose name starts with `$` are internal to Yosys and skipped.
+pub struct VcdWriter<W: Write> {
+    writer: W,
+    time: VcdTime,
+    last_timestamp: Option<u64>,
+    signals: Vec<VcdSignal>,
+}
+
+struct VcdSignal {
+    net: String,
+    id: String,
+    value: Option<LogicVec>,
+}
+
+impl<W: Write> VcdWriter<W> {
+    /// writes the header with all named nets of the module of `sim`
+    pub fn new(writer: W, sim: &Sim, time: VcdTime) -> Result<Self, SimError> {
+        let nets: Vec<&str> = sim
+            .design()
+            .module()
+            .nets
+            .iter()
+            .map(|it| it.name.as_str())
+            .filter(|it| !it.starts_with('$'))
+            .collect();
+        Self::with_nets(writer, sim, time, &nets)
+    }
+
+    /// writes the header with the given nets, in this order
+    pub fn with_nets(
+        mut writer: W,
+        sim: &Sim,
+        time: VcdTime,
+        nets: &[&str],
+    ) -> Result<Self, SimError> {
+        let module = sim.design().module();
+
+        writeln!(writer, "$version yosys-isim $end")?;
+        writeln!(writer, "$timescale 1ns $end")?;
+        writeln!(writer, "$scope module {} $end", identifier(&module.name))?;
+
+        let mut signals = Vec::new();
+        for (index, net) in nets.iter().enumerate() {
+            let width = sim.net_width(net)?;
+            let id = vcd_id(index);
+            writeln!(
+                writer,
+                "$var wire {} {} {} $end",
+                width,
+                id,
+                identifier(net)
+            )?;
+            signals.push(VcdSignal {
+                net: net.to_string(),
+                id,
+                value: None,
+            });
+        }
+
+        writeln!(writer, "$upscope $end")?;
+        writeln!(writer, "$enddefinitions $end")?;
+
+        Ok(VcdWriter {
+            writer,
+            time,
+            last_timestamp: None,
+            signals,
+        })
+    }
+
+    /// writes the nets that changed since the last sample
+    pub fn sample(&mut self, sim: &Sim) -> Result<(), SimError> {
+        let timestamp = match self.time {
+            VcdTime::Time => sim.time(),
+            VcdTime::Frame => sim.frame() as u64,
+        };
+        let is_first = self.last_timestamp.is_none();
+
+        let mut changes: Vec<(usize, LogicVec)> = Vec::new();
+        for (index, signal) in self.signals.iter().enumerate() {
+            let value = sim.get_net(&signal.net)?;
+            if signal.value.as_ref() != Some(&value) {
+                changes.push((index, value));
+            }
+        }
+
+        if is_first || !changes.is_empty() {
+            if self.last_timestamp != Some(timestamp) {
+                writeln!(self.writer, "#{}", timestamp)?;
+                self.last_timestamp = Some(timestamp);
+            }
+            if is_first {
+                writeln!(self.writer, "$dumpvars")?;
+            }
+            for (index, value) in changes {
+                let signal = &mut self.signals[index];
+                write_value(&mut self.writer, &signal.id, &value)?;
+                signal.value = Some(value);
+            }
+            if is_first {
+                writeln!(self.writer, "$end")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> Result<W, SimError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn write_value(writer: &mut impl Write, id: &str, value: &LogicVec) -> Result<(), SimError> {
+    let char = |logic: &Logic| match logic {
+        Logic::_0 => '0',
+        Logic::_1 => '1',
+        Logic::X => 'x',
+    };
+    match value.len() {
+        1 => writeln!(writer, "{}{}", char(&value[0]), id)?,
+        _ => writeln!(
+            writer,
+            "b{} {}",
+            value.iter().rev().map(char).collect::<String>(),
+            id
+        )?,
+    }
+    Ok(())
+}
+
+/// the shortest identifier of printable ASCII characters
+fn vcd_id(mut index: usize) -> String {
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return id;
+        }
+        index -= 1;
+    }
+}
+
+/// VCD identifiers must not contain whitespace
+fn identifier(name: &str) -> String {
+    name.replace(char::is_whitespace, "_")
+}
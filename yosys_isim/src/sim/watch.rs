@@ -0,0 +1,102 @@
+use crate::common::Vec4;
+use crate::model::HWireOrLogic;
+use crate::sim::FormalEvent;
+use crate::sim::Logic;
+use crate::sim::SimState;
+use crate::sim::StateRef;
+
+/// A condition on a net or the outputs of a register, checked after every frame.
+/// See [crate::sim::Sim::watch].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// fires whenever the value of the net changes
+    Changes { net: String },
+    /// fires whenever the net becomes `value`, LSB first
+    Equals { net: String, value: Vec<Logic> },
+}
+
+impl Watchpoint {
+    pub fn changes(net: &str) -> Self {
+        Watchpoint::Changes {
+            net: net.to_string(),
+        }
+    }
+
+    pub fn equals<E: Copy + Into<Logic>>(net: &str, value: &[E]) -> Self {
+        Watchpoint::Equals {
+            net: net.to_string(),
+            value: value.iter().map(|it| (*it).into()).collect(),
+        }
+    }
+
+    pub fn net(&self) -> &str {
+        match self {
+            Watchpoint::Changes { net } | Watchpoint::Equals { net, .. } => net,
+        }
+    }
+}
+
+/// A [Watchpoint] that fired in a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchHit {
+    /// as returned by [crate::sim::Sim::watch]
+    pub id: usize,
+    pub watchpoint: Watchpoint,
+    /// the value of the net after the frame
+    pub value: Vec<Logic>,
+}
+
+/// Why a loop of frames stopped early.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HaltReason {
+    /// the predicate of [crate::sim::Sim::run_until] held
+    Predicate,
+    /// at least one [Watchpoint] fired
+    Watchpoints(Vec<WatchHit>),
+    /// at least one `$assert` or `$assume` cell failed, see
+    /// [crate::sim::SimConfig::stop_on_formal_failure]
+    FormalFailures(Vec<FormalEvent>),
+    /// [crate::sim::Sim::run_until] simulated its maximum number of frames
+    MaxFrames,
+}
+
+/// The frame after which a loop of frames stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Halt {
+    pub frame: usize,
+    pub reason: HaltReason,
+}
+
+pub(super) struct WatchState {
+    pub watchpoint: Watchpoint,
+    pub wires: Vec4<HWireOrLogic>,
+    /// the value after the last frame
+    pub value: Vec<Logic>,
+}
+
+impl WatchState {
+    pub fn new(watchpoint: Watchpoint, wires: Vec4<HWireOrLogic>, sim_state: &SimState) -> Self {
+        let mut watch_state = WatchState {
+            watchpoint,
+            value: vec![Logic::X; wires.len()],
+            wires,
+        };
+        watch_state.update(sim_state);
+        watch_state
+    }
+
+    /// takes the current value of the net, without firing
+    pub fn update(&mut self, sim_state: &SimState) {
+        sim_state.get_wires_or_logic(StateRef::Cur, &self.wires, &mut self.value);
+    }
+
+    /// takes the current value of the net and tells whether the watchpoint fires
+    pub fn check(&mut self, sim_state: &SimState) -> bool {
+        let prev_value = std::mem::replace(&mut self.value, vec![Logic::X; self.wires.len()]);
+        self.update(sim_state);
+        match &self.watchpoint {
+            Watchpoint::Changes { .. } => prev_value != self.value,
+            Watchpoint::Equals { value, .. } => prev_value != *value && self.value == *value,
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::common::Random;
+use crate::common::SimError;
+use crate::model::PortDirection;
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+use crate::sim::PortHandle;
+use crate::sim::Sim;
+use std::fmt;
+
+/// The most in-port bits [Stimulus::Exhaustive] enumerates, `2^24` frames.
+pub const MAX_EXHAUSTIVE_BITS: usize = 24;
+
+/// How [CoSim::run] drives the in ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stimulus {
+    /// random values without `X` on all in ports, the same `seed` gives the same frames
+    Random { seed: u64, num_frames: usize },
+    /// every combination of the in ports, one per frame, counting up from `0`
+    Exhaustive,
+}
+
+/// Simulates a reference and a candidate module side by side, like the RTL and the
+/// synthesized netlist of the same design.
+///
+/// Both get the same values on their in ports of the same names, after every frame the
+/// out ports of the reference are compared to those of the candidate. `X` bits of the
+/// reference are don't-care.
+pub struct CoSim<'m> {
+    reference: Sim<'m>,
+    candidate: Sim<'m>,
+    /// reference and candidate handle of each in port, sorted by name
+    in_ports: Vec<(PortHandle, PortHandle)>,
+    out_ports: Vec<(PortHandle, PortHandle)>,
+}
+
+/// The outcome of [CoSim::run].
+#[derive(Debug, Clone)]
+pub struct EquivReport {
+    pub num_frames: usize,
+    /// the first frame where the modules disagree, the run stops there
+    pub divergence: Option<Divergence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// the frame of the reference after simulating the diverging frame
+    pub frame: usize,
+    pub port: String,
+    pub reference: LogicVec,
+    pub candidate: LogicVec,
+    /// the values on the in ports in the diverging frame
+    pub inputs: Vec<(String, LogicVec)>,
+}
+
+impl<'m> CoSim<'m> {
+    /// Fails if the candidate misses an in or out port of the reference, or if the widths
+    /// differ. Extra out ports of the candidate are ignored.
+    pub fn new(reference: Sim<'m>, candidate: Sim<'m>) -> Result<Self, SimError> {
+        let mut in_ports = Vec::new();
+        let mut out_ports = Vec::new();
+
+        for info in reference.design().module().ports.iter() {
+            let reference_port = reference.port(&info.name)?;
+            let candidate_port = candidate.port(&info.name)?;
+            candidate_port.check_direction(info.direction)?;
+            if candidate_port.width() != info.width {
+                return Err(SimError::PortWidthMismatch {
+                    module: candidate.design().module().name.clone(),
+                    cell: None,
+                    port: info.name.clone(),
+                    expected: info.width,
+                    actual: candidate_port.width(),
+                });
+            }
+            match info.direction {
+                PortDirection::Input => in_ports.push((reference_port, candidate_port)),
+                PortDirection::Output => out_ports.push((reference_port, candidate_port)),
+            }
+        }
+
+        for info in candidate.design().module().ports.iter() {
+            if info.direction == PortDirection::Input {
+                reference.port(&info.name)?;
+            }
+        }
+
+        Ok(CoSim {
+            reference,
+            candidate,
+            in_ports,
+            out_ports,
+        })
+    }
+
+    pub fn reference(&self) -> &Sim<'m> {
+        &self.reference
+    }
+
+    pub fn candidate(&self) -> &Sim<'m> {
+        &self.candidate
+    }
+
+    /// Simulates frames until the stimulus is used up or the modules diverge.
+    pub fn run(&mut self, stimulus: Stimulus) -> Result<EquivReport, SimError> {
+        let widths: Vec<usize> = self.in_ports.iter().map(|it| it.0.width()).collect();
+
+        let mut report = EquivReport {
+            num_frames: 0,
+            divergence: None,
+        };
+
+        match stimulus {
+            Stimulus::Random { seed, num_frames } => {
+                let mut random = Random::new(seed);
+                for _ in 0..num_frames {
+                    let inputs: Vec<LogicVec> =
+                        widths.iter().map(|width| random.logics(*width)).collect();
+                    report.num_frames += 1;
+                    if let Some(divergence) = self.step(&inputs)? {
+                        report.divergence = Some(divergence);
+                        break;
+                    }
+                }
+            }
+            Stimulus::Exhaustive => {
+                let num_bits: usize = widths.iter().sum();
+                if num_bits > MAX_EXHAUSTIVE_BITS {
+                    return Err(SimError::TooManyInputBits {
+                        module: self.reference.design().module().name.clone(),
+                        width: num_bits,
+                        max_width: MAX_EXHAUSTIVE_BITS,
+                    });
+                }
+                for combination in 0..1u64 << num_bits {
+                    let mut shift = 0;
+                    let inputs: Vec<LogicVec> = widths
+                        .iter()
+                        .map(|width| {
+                            let value = LogicVec::from_u64(*width, combination >> shift);
+                            shift += width;
+                            value
+                        })
+                        .collect();
+                    report.num_frames += 1;
+                    if let Some(divergence) = self.step(&inputs)? {
+                        report.divergence = Some(divergence);
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Applies `inputs`, one per in port in the order of their names, to both modules,
+    /// simulates one frame and compares the out ports.
+    pub fn step(&mut self, inputs: &[LogicVec]) -> Result<Option<Divergence>, SimError> {
+        for ((reference_port, candidate_port), value) in self.in_ports.iter().zip(inputs.iter()) {
+            self.reference.set_port(reference_port, value)?;
+            self.candidate.set_port(candidate_port, value)?;
+        }
+
+        self.reference.simulate()?;
+        self.candidate.simulate()?;
+
+        for (reference_port, candidate_port) in self.out_ports.iter() {
+            let reference = self.reference.get_port(reference_port);
+            let candidate = self.candidate.get_port(candidate_port);
+            let matches = reference
+                .iter()
+                .zip(candidate.iter())
+                .all(|(reference, candidate)| *reference == Logic::X || reference == candidate);
+            if !matches {
+                return Ok(Some(Divergence {
+                    frame: self.reference.frame(),
+                    port: reference_port.name().to_string(),
+                    reference,
+                    candidate,
+                    inputs: self
+                        .in_ports
+                        .iter()
+                        .zip(inputs.iter())
+                        .map(|(port, value)| (port.0.name().to_string(), value.clone()))
+                        .collect(),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl EquivReport {
+    pub fn passed(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+impl fmt::Display for EquivReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.divergence {
+            None => writeln!(f, "equivalent in {} frames", self.num_frames),
+            Some(divergence) => writeln!(f, "{}", divergence),
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|(port, value)| format!("{} = {}", port, value))
+            .collect();
+        write!(
+            f,
+            "diverged in frame {}: {} = {}, expected {} ({})",
+            self.frame,
+            self.port,
+            self.candidate,
+            self.reference,
+            inputs.join(", ")
+        )
+    }
+}
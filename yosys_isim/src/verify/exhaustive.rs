@@ -0,0 +1,366 @@
+use crate::common::SimError;
+use crate::model::Module;
+use crate::model::ModuleInPort;
+use crate::model::ModuleOutPort;
+use crate::sim::CellSimModel;
+use crate::sim::IntValue;
+use crate::sim::LaneSim;
+use crate::sim::Lanes;
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+use crate::sim::NUM_LANES;
+use crate::sim::Radix;
+use crate::verify::MAX_EXHAUSTIVE_BITS;
+use rayon::iter::IntoParallelIterator;
+use rayon::iter::ParallelIterator;
+use std::fmt;
+
+/// The most mismatches an [ExhaustiveReport] keeps, all others are only counted.
+pub const MAX_REPORTED_MISMATCHES: usize = 16;
+
+/// Evaluates a combinational module for every combination of its in ports.
+///
+/// A combination holds the in ports LSB first, in the order of their names, so for in ports
+/// `a[4]` and `b[4]` the combination `0x21` is `a = 1, b = 2`. [NUM_LANES] combinations are
+/// simulated at once on a [LaneSim], blocks of them in parallel.
+pub struct Exhaustive<'m> {
+    module: &'m Module,
+    in_ports: Vec<ModuleInPort>,
+    out_ports: Vec<ModuleOutPort>,
+    num_bits: usize,
+}
+
+/// The outcome of [Exhaustive::check].
+#[derive(Debug, Clone)]
+pub struct ExhaustiveReport {
+    pub num_combinations: u64,
+    pub num_failed: u64,
+    /// the first [MAX_REPORTED_MISMATCHES] mismatches, by combination
+    pub mismatches: Vec<ExhaustiveMismatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExhaustiveMismatch {
+    pub combination: u64,
+    pub inputs: Vec<(String, LogicVec)>,
+    pub port: String,
+    pub expected: LogicVec,
+    pub actual: LogicVec,
+}
+
+/// The out ports of every combination, see [Exhaustive::truth_table].
+/// Displays as a table for [crate::verify::TestVectors].
+#[derive(Debug, Clone)]
+pub struct TruthTable {
+    pub in_ports: Vec<(String, usize)>,
+    pub out_ports: Vec<(String, usize)>,
+    /// the out ports of each combination, `out_ports.len()` values per combination
+    pub values: Vec<IntValue<u64>>,
+}
+
+impl<'m> Exhaustive<'m> {
+    /// Fails if the module has registers or no out ports, if its in ports have more than
+    /// [MAX_EXHAUSTIVE_BITS] bits or if an out port is wider than `64` bits.
+    pub fn new(module: &'m Module) -> Result<Self, SimError> {
+        if let Some(cell) = module.cells.iter().find(|it| it.is_sequential()) {
+            return Err(SimError::NotCombinational {
+                module: module.name.clone(),
+                cell: cell.name().to_string(),
+            });
+        }
+        if module.out_ports.is_empty() {
+            return Err(SimError::NoOutPorts {
+                module: module.name.clone(),
+            });
+        }
+
+        let mut in_ports: Vec<ModuleInPort> = module.in_ports.iter().cloned().collect();
+        in_ports.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut out_ports: Vec<ModuleOutPort> = module.out_ports.iter().cloned().collect();
+        out_ports.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let num_bits: usize = in_ports.iter().map(|it| it.wires.len()).sum();
+        if num_bits > MAX_EXHAUSTIVE_BITS {
+            return Err(SimError::TooManyInputBits {
+                module: module.name.clone(),
+                width: num_bits,
+                max_width: MAX_EXHAUSTIVE_BITS,
+            });
+        }
+        if let Some(port) = out_ports.iter().find(|it| it.wires.len() > 64) {
+            return Err(SimError::PortTooWide {
+                module: module.name.clone(),
+                port: port.name.clone(),
+                width: port.wires.len(),
+                max_width: 64,
+            });
+        }
+
+        Ok(Exhaustive {
+            module,
+            in_ports,
+            out_ports,
+            num_bits,
+        })
+    }
+
+    pub fn num_combinations(&self) -> u64 {
+        1 << self.num_bits
+    }
+
+    /// the values of the in ports, in the order of their names
+    pub fn inputs(&self, combination: u64) -> Vec<u64> {
+        let mut shift = 0;
+        self.in_ports
+            .iter()
+            .map(|port| {
+                let width = port.wires.len();
+                let value = (combination >> shift) & mask(width);
+                shift += width;
+                value
+            })
+            .collect()
+    }
+
+    /// Compares the out ports of every combination to `expected`, which maps the values of
+    /// the in ports to the values of the out ports, both in the order of their names.
+    /// Bits above the width of a port are ignored, `X` bits never match. Fails if `expected`
+    /// does not return one value per out port.
+    pub fn check(
+        &self,
+        expected: impl Fn(&[u64]) -> Vec<u64> + Sync,
+    ) -> Result<ExhaustiveReport, SimError> {
+        let blocks: Vec<(u64, Vec<ExhaustiveMismatch>)> = self.evaluate(|first, values| {
+            let mut num_failed = 0;
+            let mut mismatches = Vec::new();
+            for (combination, actual) in (first..).zip(values.chunks(self.out_ports.len())) {
+                let inputs = self.inputs(combination);
+                let expected = expected(&inputs);
+                if expected.len() != self.out_ports.len() {
+                    return Err(SimError::ExpectedLengthMismatch {
+                        module: self.module.name.clone(),
+                        expected: self.out_ports.len(),
+                        actual: expected.len(),
+                    });
+                }
+                let mut failed = false;
+                for ((port, actual), expected) in self.out_ports.iter().zip(actual).zip(expected) {
+                    let width = port.wires.len();
+                    if *actual == IntValue::Known(expected & mask(width)) {
+                        continue;
+                    }
+                    failed = true;
+                    if mismatches.len() < MAX_REPORTED_MISMATCHES {
+                        mismatches.push(ExhaustiveMismatch {
+                            combination,
+                            inputs: self
+                                .in_ports
+                                .iter()
+                                .zip(inputs.iter())
+                                .map(|(port, value)| {
+                                    let value = LogicVec::from_u64(port.wires.len(), *value);
+                                    (port.name.clone(), value)
+                                })
+                                .collect(),
+                            port: port.name.clone(),
+                            expected: LogicVec::from_u64(width, expected),
+                            actual: to_logic_vec(*actual, width),
+                        });
+                    }
+                }
+                if failed {
+                    num_failed += 1;
+                }
+            }
+            Ok((num_failed, mismatches))
+        })?;
+
+        let mut report = ExhaustiveReport {
+            num_combinations: self.num_combinations(),
+            num_failed: 0,
+            mismatches: Vec::new(),
+        };
+        for (num_failed, mismatches) in blocks {
+            report.num_failed += num_failed;
+            let num_free = MAX_REPORTED_MISMATCHES - report.mismatches.len();
+            report
+                .mismatches
+                .extend(mismatches.into_iter().take(num_free));
+        }
+        Ok(report)
+    }
+
+    /// The out ports of every combination. Takes `2^n` times the out ports in memory.
+    pub fn truth_table(&self) -> Result<TruthTable, SimError> {
+        let blocks = self.evaluate(|_, values| Ok(values.to_vec()))?;
+        Ok(TruthTable {
+            in_ports: self
+                .in_ports
+                .iter()
+                .map(|it| (it.name.clone(), it.wires.len()))
+                .collect(),
+            out_ports: self
+                .out_ports
+                .iter()
+                .map(|it| (it.name.clone(), it.wires.len()))
+                .collect(),
+            values: blocks.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Simulates the combinations in blocks of [NUM_LANES] and calls `block` with the first
+    /// combination and the out ports of each combination of the block.
+    fn evaluate<R: Send>(
+        &self,
+        block: impl Fn(u64, &[IntValue<u64>]) -> Result<R, SimError> + Sync,
+    ) -> Result<Vec<R>, SimError> {
+        let num_combinations = self.num_combinations();
+        let num_blocks = num_combinations.div_ceil(NUM_LANES as u64);
+
+        (0..num_blocks)
+            .into_par_iter()
+            .map_init(
+                || LaneSim::new(self.module),
+                |sim, index| {
+                    let first = index * NUM_LANES as u64;
+                    let num_lanes = (num_combinations - first).min(NUM_LANES as u64) as usize;
+
+                    let mut bit = 0;
+                    for port in self.in_ports.iter() {
+                        let lanes: Vec<Lanes> = (bit..bit + port.wires.len())
+                            .map(|bit| {
+                                let mut lanes = Lanes::default();
+                                for lane in 0..NUM_LANES {
+                                    let combination = first + lane as u64;
+                                    lanes.value |= ((combination >> bit) & 1) << lane;
+                                }
+                                lanes
+                            })
+                            .collect();
+                        sim.set_dynamic(port, &lanes);
+                        bit += port.wires.len();
+                    }
+
+                    sim.simulate()?;
+
+                    let outputs: Vec<Vec<Lanes>> = self
+                        .out_ports
+                        .iter()
+                        .map(|port| sim.get_dynamic(port))
+                        .collect();
+                    let values: Vec<IntValue<u64>> = (0..num_lanes)
+                        .flat_map(|lane| outputs.iter().map(move |lanes| lane_value(lanes, lane)))
+                        .collect();
+
+                    block(first, &values)
+                },
+            )
+            .collect()
+    }
+}
+
+impl ExhaustiveReport {
+    pub fn passed(&self) -> bool {
+        self.num_failed == 0
+    }
+}
+
+impl fmt::Display for ExhaustiveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} combinations passed",
+            self.num_combinations - self.num_failed,
+            self.num_combinations
+        )?;
+        for mismatch in self.mismatches.iter() {
+            let inputs: Vec<String> = mismatch
+                .inputs
+                .iter()
+                .map(|(port, value)| format!("{} = {}", port, value))
+                .collect();
+            writeln!(
+                f,
+                "  {}: {} = {}, expected {}",
+                inputs.join(", "),
+                mismatch.port,
+                mismatch.actual,
+                mismatch.expected
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl TruthTable {
+    /// the out ports of `combination`, in the order of their names
+    pub fn row(&self, combination: u64) -> &[IntValue<u64>] {
+        let start = combination as usize * self.out_ports.len();
+        &self.values[start..start + self.out_ports.len()]
+    }
+}
+
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns: Vec<&str> = self
+            .in_ports
+            .iter()
+            .chain(self.out_ports.iter())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        writeln!(f, "{}", columns.join(", "))?;
+
+        let num_rows = self.values.len() / self.out_ports.len().max(1);
+        for combination in 0..num_rows as u64 {
+            let mut shift = 0;
+            let mut values: Vec<String> = Vec::new();
+            for (_, width) in self.in_ports.iter() {
+                let value = LogicVec::from_u64(*width, combination >> shift);
+                values.push(value.to_literal(Radix::Hex));
+                shift += width;
+            }
+            for ((_, width), value) in self.out_ports.iter().zip(self.row(combination)) {
+                values.push(to_logic_vec(*value, *width).to_literal(Radix::Hex));
+            }
+            writeln!(f, "{}", values.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+fn mask(width: usize) -> u64 {
+    match width {
+        64.. => !0,
+        _ => (1 << width) - 1,
+    }
+}
+
+fn lane_value(lanes: &[Lanes], lane: usize) -> IntValue<u64> {
+    let mut value = 0;
+    let mut x_mask = 0;
+    for (bit, lanes) in lanes.iter().enumerate() {
+        match lanes.get(lane) {
+            Logic::_0 => {}
+            Logic::_1 => value |= 1 << bit,
+            Logic::X => x_mask |= 1 << bit,
+        }
+    }
+    match x_mask {
+        0 => IntValue::Known(value),
+        _ => IntValue::Unknown { value, x_mask },
+    }
+}
+
+fn to_logic_vec(value: IntValue<u64>, width: usize) -> LogicVec {
+    let x_mask = match value {
+        IntValue::Known(_) => 0,
+        IntValue::Unknown { x_mask, .. } => x_mask,
+    };
+    let mut logics = LogicVec::from_u64(width, *value.value());
+    for (bit, logic) in logics.iter_mut().enumerate() {
+        if (x_mask >> bit) & 1 == 1 {
+            *logic = Logic::X;
+        }
+    }
+    logics
+}
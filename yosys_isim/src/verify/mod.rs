@@ -0,0 +1,9 @@
+mod equiv;
+mod exhaustive;
+mod property;
+mod vectors;
+
+pub use equiv::*;
+pub use exhaustive::*;
+pub use property::*;
+pub use vectors::*;
@@ -0,0 +1,438 @@
+use crate::common::Random;
+use crate::common::SimError;
+use crate::model::Module;
+use crate::model::PortDirection;
+use crate::sim::Design;
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+use crate::sim::PortHandle;
+use crate::sim::Radix;
+use crate::sim::Sim;
+use crate::sim::SimConfig;
+use crate::verify::TestVectorRow;
+use crate::verify::TestVectors;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+
+/// The values a [PropertyTest] chooses for an in port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortConstraint {
+    /// any value without `X`
+    Any,
+    /// a value in `min..=max`
+    Range { min: u64, max: u64 },
+    /// a value with exactly one bit set
+    OneHot,
+    /// one of the values
+    OneOf(Vec<u64>),
+    /// `0` and `1` in turns, starting with `0`, like a clock
+    Toggle,
+}
+
+/// Drives the in ports of a module with random values, checks an invariant after every
+/// frame and shrinks a failing sequence to a short reproducer.
+///
+/// Each run starts from the power-up state and simulates up to `num_frames` frames. The
+/// values of a port are drawn according to its [PortConstraint] and held for its `hold`
+/// frames. Shrinking drops frames and replaces values by smaller ones that still satisfy
+/// the constraint, as long as the invariant still fails. Dropping frames ignores `hold`
+/// and [PortConstraint::Toggle].
+pub struct PropertyTest<'m> {
+    module: &'m Module,
+    pub sim_config: SimConfig,
+    pub seed: u64,
+    pub num_runs: usize,
+    pub num_frames: usize,
+    /// the most sequences replayed while shrinking
+    pub max_shrinks: usize,
+    /// the file the reproducer of a failure is written to
+    pub dump: Option<String>,
+    /// all in ports, sorted by name
+    ports: Vec<PortStimulus>,
+}
+
+#[derive(Debug, Clone)]
+struct PortStimulus {
+    name: String,
+    width: usize,
+    constraint: PortConstraint,
+    hold: usize,
+}
+
+/// The outcome of [PropertyTest::check].
+#[derive(Debug, Clone)]
+pub struct PropertyReport {
+    /// the runs until the first failure, all runs if there is none
+    pub num_runs: usize,
+    pub failure: Option<PropertyFailure>,
+}
+
+/// A shrunk sequence of in-port values whose last frame fails the invariant.
+#[derive(Debug, Clone)]
+pub struct PropertyFailure {
+    pub run: usize,
+    /// the seed of the run, before shrinking
+    pub seed: u64,
+    /// the frames until the invariant failed, before shrinking
+    pub num_frames: usize,
+    pub columns: Vec<String>,
+    /// the values of the in ports of each frame, in the order of `columns`
+    pub sequence: Vec<Vec<LogicVec>>,
+}
+
+impl<'m> PropertyTest<'m> {
+    /// 100 runs of 100 frames, all in ports [PortConstraint::Any]
+    pub fn new(module: &'m Module) -> Self {
+        let ports = module
+            .ports
+            .iter()
+            .filter(|it| it.direction == PortDirection::Input)
+            .map(|it| PortStimulus {
+                name: it.name.clone(),
+                width: it.width,
+                constraint: PortConstraint::Any,
+                hold: 1,
+            })
+            .collect();
+
+        PropertyTest {
+            module,
+            sim_config: SimConfig::default(),
+            seed: 0,
+            num_runs: 100,
+            num_frames: 100,
+            max_shrinks: 10_000,
+            dump: None,
+            ports,
+        }
+    }
+
+    pub fn constrain(&mut self, port: &str, constraint: PortConstraint) -> Result<(), SimError> {
+        let width = self.stimulus(port)?.width;
+        let max = max_value(width);
+        let legal = match &constraint {
+            PortConstraint::Any | PortConstraint::Toggle => true,
+            PortConstraint::Range {
+                min,
+                max: range_max,
+            } => width <= 64 && min <= range_max && *range_max <= max,
+            PortConstraint::OneHot => (1..=64).contains(&width),
+            PortConstraint::OneOf(values) => {
+                width <= 64 && !values.is_empty() && values.iter().all(|it| *it <= max)
+            }
+        };
+        if !legal {
+            return Err(SimError::IllegalConstraint {
+                module: self.module.name.clone(),
+                port: port.to_string(),
+                msg: format!("{:?} does not fit into [{}] bits", constraint, width),
+            });
+        }
+
+        self.stimulus_mut(port)?.constraint = constraint;
+        Ok(())
+    }
+
+    /// holds each value of `port` for `num_frames` frames
+    pub fn hold(&mut self, port: &str, num_frames: usize) -> Result<(), SimError> {
+        self.stimulus_mut(port)?.hold = num_frames.max(1);
+        Ok(())
+    }
+
+    /// Runs until `invariant` returns `false` and shrinks the failing sequence.
+    /// Writes the reproducer to [PropertyTest::dump] if set.
+    pub fn check(
+        &self,
+        mut invariant: impl FnMut(&mut Sim<'m>) -> Result<bool, SimError>,
+    ) -> Result<PropertyReport, SimError> {
+        let design = Arc::new(Design::try_with_config(
+            self.module,
+            self.sim_config.clone(),
+        )?);
+        let mut seeds = Random::new(self.seed);
+
+        for run in 0..self.num_runs {
+            let seed = seeds.next_u64();
+            let mut sequence = self.generate(seed);
+
+            if let Some(frame) = self.replay(&design, &sequence, &mut invariant)? {
+                sequence.truncate(frame + 1);
+                let failure = PropertyFailure {
+                    run,
+                    seed,
+                    num_frames: frame + 1,
+                    columns: self.ports.iter().map(|it| it.name.clone()).collect(),
+                    sequence: self.shrink(&design, sequence, &mut invariant)?,
+                };
+                if let Some(file_name) = &self.dump {
+                    fs::write(file_name, failure.table())?;
+                }
+                return Ok(PropertyReport {
+                    num_runs: run + 1,
+                    failure: Some(failure),
+                });
+            }
+        }
+
+        Ok(PropertyReport {
+            num_runs: self.num_runs,
+            failure: None,
+        })
+    }
+
+    fn generate(&self, seed: u64) -> Vec<Vec<LogicVec>> {
+        let mut random = Random::new(seed);
+        let mut sequence: Vec<Vec<LogicVec>> = Vec::with_capacity(self.num_frames);
+
+        for frame in 0..self.num_frames {
+            let values = self
+                .ports
+                .iter()
+                .enumerate()
+                .map(|(index, port)| match (frame % port.hold, sequence.last()) {
+                    (1.., Some(previous)) => previous[index].clone(),
+                    _ => port.draw(&mut random, frame / port.hold),
+                })
+                .collect();
+            sequence.push(values);
+        }
+
+        sequence
+    }
+
+    /// the first frame that fails the invariant, on a new [Sim] of `design`
+    fn replay(
+        &self,
+        design: &Arc<Design<'m>>,
+        sequence: &[Vec<LogicVec>],
+        invariant: &mut impl FnMut(&mut Sim<'m>) -> Result<bool, SimError>,
+    ) -> Result<Option<usize>, SimError> {
+        let mut sim = design.spawn();
+        let ports: Vec<PortHandle> = self
+            .ports
+            .iter()
+            .map(|it| sim.port(&it.name))
+            .collect::<Result<_, _>>()?;
+
+        for (frame, values) in sequence.iter().enumerate() {
+            for (port, value) in ports.iter().zip(values.iter()) {
+                sim.set_port(port, value)?;
+            }
+            sim.simulate()?;
+            if !invariant(&mut sim)? {
+                return Ok(Some(frame));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn shrink(
+        &self,
+        design: &Arc<Design<'m>>,
+        mut sequence: Vec<Vec<LogicVec>>,
+        invariant: &mut impl FnMut(&mut Sim<'m>) -> Result<bool, SimError>,
+    ) -> Result<Vec<Vec<LogicVec>>, SimError> {
+        let mut budget = self.max_shrinks;
+
+        loop {
+            let mut changed = false;
+
+            // drops ever smaller chunks of frames
+            let mut chunk = sequence.len() / 2;
+            while chunk > 0 {
+                let mut start = 0;
+                while start < sequence.len() && budget > 0 {
+                    let end = (start + chunk).min(sequence.len());
+                    if end - start == sequence.len() {
+                        break;
+                    }
+                    let candidate = [&sequence[..start], &sequence[end..]].concat();
+                    budget -= 1;
+                    match self.replay(design, &candidate, invariant)? {
+                        Some(frame) => {
+                            sequence = candidate;
+                            sequence.truncate(frame + 1);
+                            changed = true;
+                        }
+                        None => start += chunk,
+                    }
+                }
+                chunk /= 2;
+            }
+
+            // replaces values by smaller ones
+            let mut frame = 0;
+            while frame < sequence.len() {
+                for (index, port) in self.ports.iter().enumerate() {
+                    for value in port.smaller(&sequence[frame][index]) {
+                        if budget == 0 || frame >= sequence.len() {
+                            break;
+                        }
+                        let mut candidate = sequence.clone();
+                        candidate[frame][index] = value;
+                        budget -= 1;
+                        if let Some(failed_frame) = self.replay(design, &candidate, invariant)? {
+                            candidate.truncate(failed_frame + 1);
+                            sequence = candidate;
+                            changed = true;
+                            break;
+                        }
+                    }
+                }
+                frame += 1;
+            }
+
+            if !changed || budget == 0 {
+                return Ok(sequence);
+            }
+        }
+    }
+
+    fn stimulus(&self, port: &str) -> Result<&PortStimulus, SimError> {
+        self.module.port_info(port)?;
+        self.ports
+            .iter()
+            .find(|it| it.name == port)
+            .ok_or_else(|| SimError::WrongPortDirection {
+                module: self.module.name.clone(),
+                port: port.to_string(),
+                direction: PortDirection::Output,
+            })
+    }
+
+    fn stimulus_mut(&mut self, port: &str) -> Result<&mut PortStimulus, SimError> {
+        self.stimulus(port)?;
+        Ok(self.ports.iter_mut().find(|it| it.name == port).unwrap())
+    }
+}
+
+impl PortStimulus {
+    /// the `index`th value drawn for the port
+    fn draw(&self, random: &mut Random, index: usize) -> LogicVec {
+        let value = match &self.constraint {
+            PortConstraint::Any => return random.logics(self.width),
+            PortConstraint::Range { min, max } => match (max - min).checked_add(1) {
+                Some(num_values) => min + random.below(num_values),
+                None => random.next_u64(),
+            },
+            PortConstraint::OneHot => 1 << random.below(self.width as u64),
+            PortConstraint::OneOf(values) => values[random.below(values.len() as u64) as usize],
+            PortConstraint::Toggle => index as u64 % 2,
+        };
+        LogicVec::from_u64(self.width, value)
+    }
+
+    /// values that still satisfy the constraint and are smaller than `value`, simplest first
+    fn smaller(&self, value: &LogicVec) -> Vec<LogicVec> {
+        let candidates: Vec<LogicVec> = match &self.constraint {
+            PortConstraint::Any => {
+                let mut cleared = value.clone();
+                if let Some(bit) = cleared.iter_mut().rev().find(|it| **it == Logic::_1) {
+                    *bit = Logic::_0;
+                }
+                vec![LogicVec::from_u64(self.width, 0), cleared]
+            }
+            PortConstraint::Range { min, .. } => {
+                let value = to_u64(value);
+                [*min, min + (value - min) / 2]
+                    .map(|it| LogicVec::from_u64(self.width, it))
+                    .to_vec()
+            }
+            PortConstraint::OneHot => [1, to_u64(value) >> 1]
+                .map(|it| LogicVec::from_u64(self.width, it))
+                .to_vec(),
+            PortConstraint::OneOf(values) => values
+                .iter()
+                .map(|it| LogicVec::from_u64(self.width, *it))
+                .collect(),
+            PortConstraint::Toggle => Vec::new(),
+        };
+
+        let mut smaller: Vec<LogicVec> = Vec::new();
+        for candidate in candidates {
+            if is_less(&candidate, value) && !smaller.contains(&candidate) {
+                smaller.push(candidate);
+            }
+        }
+        smaller
+    }
+}
+
+impl PropertyReport {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl PropertyFailure {
+    /// the reproducer as a table for [TestVectors], one row per frame
+    pub fn table(&self) -> String {
+        let mut table = format!("{}\n", self.columns.join(", "));
+        for values in self.sequence.iter() {
+            let values: Vec<String> = values.iter().map(|it| it.to_literal(Radix::Hex)).collect();
+            table.push_str(&format!("{}\n", values.join(", ")));
+        }
+        table
+    }
+
+    /// the reproducer, replaying it with [TestVectors::run_with] simulates the same frames
+    pub fn to_test_vectors(&self) -> TestVectors {
+        TestVectors {
+            columns: self.columns.clone(),
+            rows: self
+                .sequence
+                .iter()
+                .enumerate()
+                .map(|(index, values)| TestVectorRow {
+                    line: index + 2,
+                    values: values.iter().map(|it| it.to_literal(Radix::Hex)).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for PropertyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            None => writeln!(f, "{} runs passed", self.num_runs),
+            Some(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+impl fmt::Display for PropertyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "invariant failed in frame {} of run {} with seed {:#x}, shrunk to {} frames:",
+            self.num_frames,
+            self.run,
+            self.seed,
+            self.sequence.len()
+        )?;
+        write!(f, "{}", self.table())
+    }
+}
+
+fn max_value(width: usize) -> u64 {
+    match width {
+        64.. => !0,
+        _ => (1 << width) - 1,
+    }
+}
+
+fn to_u64(value: &LogicVec) -> u64 {
+    value.to_int().value()[0]
+}
+
+/// compares values without `X` of the same width
+fn is_less(a: &LogicVec, b: &LogicVec) -> bool {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .find(|(a, b)| a != b)
+        .is_some_and(|(a, _)| *a == Logic::_0)
+}
@@ -0,0 +1,222 @@
+use crate::common::SimError;
+use crate::model::PortDirection;
+use crate::sim::Logic;
+use crate::sim::LogicVec;
+use crate::sim::PortHandle;
+use crate::sim::Sim;
+use std::fmt;
+use std::fs;
+
+/// A table of stimulus and expected outputs, one frame per row.
+///
+/// The first row holds the port names, the other rows literals like `8'h3f`, `4'b10x1` or
+/// plain decimal numbers. Unsized literals take the width of their port. `X` bits of
+/// expected outputs are don't-care, so `x` skips the comparison. Empty lines and lines
+/// starting with `#` are ignored.
+#[derive(Debug, Clone)]
+pub struct TestVectors {
+    pub columns: Vec<String>,
+    pub rows: Vec<TestVectorRow>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestVectorRow {
+    /// the line in the table, starting at `1`
+    pub line: usize,
+    pub values: Vec<String>,
+}
+
+/// The outcome of [TestVectors::run].
+#[derive(Debug, Clone, Default)]
+pub struct VectorReport {
+    pub num_rows: usize,
+    pub mismatches: Vec<VectorMismatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorMismatch {
+    pub line: usize,
+    /// the frame of the [Sim] after simulating the row
+    pub frame: usize,
+    pub port: String,
+    pub expected: LogicVec,
+    pub actual: LogicVec,
+}
+
+impl TestVectors {
+    pub fn read_from_file(file_name: &str) -> Result<TestVectors, SimError> {
+        Self::parse(&fs::read_to_string(file_name)?)
+    }
+
+    pub fn parse(table: &str) -> Result<TestVectors, SimError> {
+        let mut lines = table
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let Some((_, header)) = lines.next() else {
+            return Err(SimError::IllegalTestVectors {
+                line: 1,
+                msg: "the header with the port names is missing".to_string(),
+            });
+        };
+        let columns: Vec<String> = split(header);
+
+        let rows = lines
+            .map(|(line, text)| {
+                let values = split(text);
+                match values.len() == columns.len() {
+                    true => Ok(TestVectorRow { line, values }),
+                    false => Err(SimError::IllegalTestVectors {
+                        line,
+                        msg: format!(
+                            "expected [{}] values but found [{}]",
+                            columns.len(),
+                            values.len()
+                        ),
+                    }),
+                }
+            })
+            .collect::<Result<Vec<_>, SimError>>()?;
+
+        Ok(TestVectors { columns, rows })
+    }
+
+    /// Applies the in ports of each row, simulates one frame and compares the out ports.
+    /// Fails on unknown ports and illegal values before simulating anything.
+    pub fn run(&self, sim: &mut Sim) -> Result<VectorReport, SimError> {
+        self.run_with(sim, |_| Ok(()))
+    }
+
+    /// like [TestVectors::run], calls `after_frame` after simulating each row
+    pub fn run_with(
+        &self,
+        sim: &mut Sim,
+        mut after_frame: impl FnMut(&mut Sim) -> Result<(), SimError>,
+    ) -> Result<VectorReport, SimError> {
+        let ports: Vec<PortHandle> = self
+            .columns
+            .iter()
+            .map(|column| sim.port(column))
+            .collect::<Result<_, _>>()?;
+
+        let rows: Vec<Vec<LogicVec>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.values
+                    .iter()
+                    .zip(ports.iter())
+                    .map(|(value, port)| parse_value(value, port, row.line))
+                    .collect()
+            })
+            .collect::<Result<_, SimError>>()?;
+
+        let mut report = VectorReport {
+            num_rows: rows.len(),
+            mismatches: Vec::new(),
+        };
+
+        for (row, values) in self.rows.iter().zip(rows.iter()) {
+            for (port, value) in ports.iter().zip(values.iter()) {
+                if port.direction() == PortDirection::Input {
+                    sim.set_port(port, value)?;
+                }
+            }
+
+            sim.simulate()?;
+            after_frame(sim)?;
+
+            for (port, expected) in ports.iter().zip(values.iter()) {
+                if port.direction() == PortDirection::Output {
+                    let actual = sim.get_port(port);
+                    let matches = actual
+                        .iter()
+                        .zip(expected.iter())
+                        .all(|(actual, expected)| *expected == Logic::X || actual == expected);
+                    if !matches {
+                        report.mismatches.push(VectorMismatch {
+                            line: row.line,
+                            frame: sim.frame(),
+                            port: port.name().to_string(),
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl VectorReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn num_failed_rows(&self) -> usize {
+        let mut lines: Vec<usize> = self.mismatches.iter().map(|it| it.line).collect();
+        lines.dedup();
+        lines.len()
+    }
+}
+
+impl fmt::Display for VectorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} rows passed",
+            self.num_rows - self.num_failed_rows(),
+            self.num_rows
+        )?;
+        for mismatch in self.mismatches.iter() {
+            writeln!(
+                f,
+                "  line {}, frame {}: {} = {}, expected {}",
+                mismatch.line, mismatch.frame, mismatch.port, mismatch.actual, mismatch.expected
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn split(line: &str) -> Vec<String> {
+    line.split(',').map(|it| it.trim().to_string()).collect()
+}
+
+fn parse_value(value: &str, port: &PortHandle, line: usize) -> Result<LogicVec, SimError> {
+    let logics: LogicVec = value.parse()?;
+
+    let is_sized = value
+        .split_once('\'')
+        .is_some_and(|(size, _)| !size.trim().is_empty());
+    if is_sized && logics.len() != port.width() {
+        return Err(SimError::IllegalTestVectors {
+            line,
+            msg: format!(
+                "[{}] does not have the width [{}] of port [{}]",
+                value,
+                port.width(),
+                port.name()
+            ),
+        });
+    }
+
+    if !logics.fits(port.width()) {
+        return Err(SimError::IllegalTestVectors {
+            line,
+            msg: format!(
+                "[{}] does not fit into the width [{}] of port [{}]",
+                value,
+                port.width(),
+                port.name()
+            ),
+        });
+    }
+
+    // unsized literals are at least 32 bits
+    Ok(logics.resized(port.width()))
+}
@@ -0,0 +1,136 @@
+use crate::CliError;
+use yosys_isim::sim::Clock;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Radix;
+
+pub const USAGE: &str = "\
+usage: yosys-isim <netlist.json> [options]
+
+options:
+  --top <module>              the module to simulate, optional if the netlist has only one
+  --set <port>=<literal>      drives an in port, like `--set addr=8'h3f`
+  --clock <port>[:<period>[:<phase>]]
+                              drives an in port with a clock, the period defaults to 10
+  --cycles <n>                the rising edges of the first clock to simulate, or the frames
+                              if there is no clock, defaults to 1
+  --stimulus <table.csv>      applies and checks the rows of a test-vector table instead
+  --vcd <file.vcd>            writes the waveforms of all named nets
+  --print <port>,...          prints the ports after every frame, otherwise the out ports
+                              are printed once at the end
+  --radix b|o|d|h             the radix of printed values, defaults to h
+  --engine interpreter|tape|jit
+  -h, --help";
+
+/// The command line of `yosys-isim`.
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub netlist: String,
+    pub top: Option<String>,
+    pub sets: Vec<(String, String)>,
+    pub clocks: Vec<(String, Clock)>,
+    pub cycles: usize,
+    pub stimulus: Option<String>,
+    pub vcd: Option<String>,
+    pub print: Vec<String>,
+    pub radix: Radix,
+    pub engine: Engine,
+}
+
+impl Args {
+    /// `args` without the name of the executable
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, CliError> {
+        let mut netlist: Option<String> = None;
+        let mut parsed = Args {
+            netlist: String::new(),
+            top: None,
+            sets: Vec::new(),
+            clocks: Vec::new(),
+            cycles: 1,
+            stimulus: None,
+            vcd: None,
+            print: Vec::new(),
+            radix: Radix::Hex,
+            engine: Engine::Interpreter,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Err(CliError::Help),
+                "--top" => parsed.top = Some(value()?),
+                "--set" => {
+                    let value = value()?;
+                    let (port, literal) = value.split_once('=').ok_or_else(|| {
+                        CliError::Usage(format!("expected <port>=<literal> but got [{}]", value))
+                    })?;
+                    parsed.sets.push((port.to_string(), literal.to_string()));
+                }
+                "--clock" => parsed.clocks.push(parse_clock(&value()?)?),
+                "--cycles" => parsed.cycles = parse_number(&value()?)? as usize,
+                "--stimulus" => parsed.stimulus = Some(value()?),
+                "--vcd" => parsed.vcd = Some(value()?),
+                "--print" => parsed
+                    .print
+                    .extend(value()?.split(',').map(|it| it.trim().to_string())),
+                "--radix" => {
+                    parsed.radix = match value()?.as_str() {
+                        "b" => Radix::Bin,
+                        "o" => Radix::Oct,
+                        "d" => Radix::Dec,
+                        "h" => Radix::Hex,
+                        radix => return Err(CliError::Usage(format!("unknown radix [{}]", radix))),
+                    }
+                }
+                "--engine" => {
+                    parsed.engine = match value()?.as_str() {
+                        "interpreter" => Engine::Interpreter,
+                        "tape" => Engine::Tape,
+                        #[cfg(feature = "jit")]
+                        "jit" => Engine::Jit,
+                        engine => {
+                            return Err(CliError::Usage(format!("unknown engine [{}]", engine)));
+                        }
+                    }
+                }
+                option if option.starts_with('-') => {
+                    return Err(CliError::Usage(format!("unknown option [{}]", option)));
+                }
+                _ if netlist.is_none() => netlist = Some(arg),
+                _ => return Err(CliError::Usage(format!("unexpected argument [{}]", arg))),
+            }
+        }
+
+        parsed.netlist =
+            netlist.ok_or_else(|| CliError::Usage("the netlist is missing".to_string()))?;
+        Ok(parsed)
+    }
+}
+
+fn parse_clock(spec: &str) -> Result<(String, Clock), CliError> {
+    let mut parts = spec.split(':');
+    let port = parts.next().unwrap_or_default().to_string();
+    let mut clock = Clock::new(10);
+    if let Some(period) = parts.next() {
+        clock.period = parse_number(period)?;
+    }
+    if let Some(phase) = parts.next() {
+        clock.phase = parse_number(phase)?;
+    }
+    if parts.next().is_some() || port.is_empty() {
+        return Err(CliError::Usage(format!(
+            "expected <port>[:<period>[:<phase>]] but got [{}]",
+            spec
+        )));
+    }
+    Ok((port, clock))
+}
+
+fn parse_number(value: &str) -> Result<u64, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("expected a number but got [{}]", value)))
+}
@@ -0,0 +1,163 @@
+use rustyline::Config;
+use rustyline::Context;
+use rustyline::Editor;
+use rustyline::Helper;
+use rustyline::completion::Completer;
+use rustyline::completion::FilenameCompleter;
+use rustyline::completion::Pair;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::history::History;
+use rustyline::validate::Validator;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Radix;
+use yosys_isim_cli::Repl;
+use yosys_isim_cli::Reply;
+
+/// Completes commands, modules, nets and the file names of `load`.
+struct ReplHelper<'m> {
+    repl: Repl<'m>,
+    file_names: FilenameCompleter,
+}
+
+impl Completer for ReplHelper<'_> {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        if line.trim_start().starts_with("load ") {
+            return self.file_names.complete(line, pos, ctx);
+        }
+        let (start, candidates) = self.repl.completions(line, pos);
+        let candidates = candidates
+            .into_iter()
+            .map(|it| Pair {
+                display: it.clone(),
+                replacement: it,
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper<'_> {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper<'_> {}
+
+impl Validator for ReplHelper<'_> {}
+
+impl Helper for ReplHelper<'_> {}
+
+/// Why a [session] ended.
+enum Exit {
+    Quit,
+    Load(Vec<Module>, Radix),
+}
+
+/// `yosys-isim-repl [netlist.json]`, the history is kept in `~/.yosys_isim_history`
+fn main() -> ExitCode {
+    let history_file =
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".yosys_isim_history"));
+    let mut history = DefaultHistory::new();
+    if let Some(history_file) = &history_file {
+        let _ = history.load(history_file);
+    }
+
+    let mut lines: Vec<String> = std::env::args()
+        .skip(1)
+        .take(1)
+        .map(|netlist| format!("load {}", netlist))
+        .collect();
+
+    // the [Repl] borrows the modules, so every `load` ends the session on the old ones
+    let mut modules = Vec::new();
+    let mut radix = Radix::Hex;
+    let mut loaded = false;
+    loop {
+        let exit = match session(&modules, radix, loaded, &mut history, &mut lines) {
+            Ok(exit) => exit,
+            Err(err) => {
+                eprintln!("{}", err);
+                return ExitCode::from(2);
+            }
+        };
+        match exit {
+            Exit::Quit => break,
+            Exit::Load(new_modules, new_radix) => {
+                modules = new_modules;
+                radix = new_radix;
+                loaded = true;
+            }
+        }
+    }
+
+    if let Some(history_file) = &history_file {
+        let _ = history.save(history_file);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Executes `lines` and then the lines read from the terminal on `modules`,
+/// until `quit`, the end of the input or a `load`.
+fn session(
+    modules: &[Module],
+    radix: Radix,
+    loaded: bool,
+    history: &mut DefaultHistory,
+    lines: &mut Vec<String>,
+) -> rustyline::Result<Exit> {
+    let mut repl = Repl::new(modules, radix);
+    if loaded {
+        match repl.loaded() {
+            Ok(output) => println!("{}", output),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
+
+    let mut editor: Editor<ReplHelper, DefaultHistory> =
+        Editor::with_history(Config::default(), std::mem::take(history))?;
+    editor.set_helper(Some(ReplHelper {
+        repl,
+        file_names: FilenameCompleter::new(),
+    }));
+
+    let exit = loop {
+        let line = match lines.pop() {
+            Some(line) => line,
+            None => match editor.readline("> ") {
+                Ok(line) => {
+                    let _ = editor.add_history_entry(line.as_str());
+                    line
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break Exit::Quit,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    break Exit::Quit;
+                }
+            },
+        };
+
+        let repl = &mut editor.helper_mut().unwrap().repl;
+        match repl.execute(&line) {
+            Ok(Reply::Output(output)) if output.is_empty() => {}
+            Ok(Reply::Output(output)) => println!("{}", output),
+            Ok(Reply::Load(modules)) => break Exit::Load(modules, repl.radix()),
+            Ok(Reply::Quit) => break Exit::Quit,
+            Err(err) => eprintln!("{}", err),
+        }
+    };
+
+    *history = std::mem::take(editor.history_mut());
+    Ok(exit)
+}
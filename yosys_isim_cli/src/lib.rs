@@ -0,0 +1,203 @@
+mod args;
+mod repl;
+
+pub use args::*;
+pub use repl::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use thiserror::Error;
+use yosys_isim::common::SimError;
+use yosys_isim::json::parse_modules_from_file;
+use yosys_isim::model::Module;
+use yosys_isim::model::PortDirection;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::PortHandle;
+use yosys_isim::sim::Radix;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::VcdTime;
+use yosys_isim::sim::VcdWriter;
+use yosys_isim::verify::TestVectors;
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("{USAGE}")]
+    Help,
+    /// a command of the REPL that cannot be executed
+    #[error("{0}")]
+    Command(String),
+    #[error("{0}")]
+    Sim(#[from] SimError),
+    #[error("io error: {0}")]
+    IOError(#[from] std::io::Error),
+}
+
+/// Runs `yosys-isim` with `args`, without the name of the executable, and prints to `out`.
+/// Returns whether all rows of the stimulus passed, `true` if there is none, and no `$assert`
+/// or `$assume` cell failed.
+pub fn run(args: impl IntoIterator<Item = String>, out: &mut impl Write) -> Result<bool, CliError> {
+    let args = Args::parse(args)?;
+
+    let modules = parse_modules_from_file(&args.netlist)?;
+    let module = select_top(&modules, args.top.as_deref())?;
+
+    let mut sim = Sim::try_with_config(
+        module,
+        SimConfig {
+            engine: args.engine,
+            ..Default::default()
+        },
+    )?;
+
+    for (name, literal) in args.sets.iter() {
+        let port = sim.port(name)?;
+        let value: LogicVec = literal.parse()?;
+        if !value.fits(port.width()) {
+            return Err(CliError::Usage(format!(
+                "[{}] does not fit into the width [{}] of port [{}]",
+                literal,
+                port.width(),
+                name
+            )));
+        }
+        sim.set_port(&port, &value.resized(port.width()))?;
+    }
+
+    let mut clock_ports: Vec<PortHandle> = Vec::new();
+    for (name, clock) in args.clocks.iter() {
+        let port = sim.port(name)?;
+        if port.width() != 1 {
+            return Err(CliError::Usage(format!(
+                "the clock [{}] is not 1 bit wide",
+                name
+            )));
+        }
+        sim.add_clock(&port.to_in_port()?.into_width::<1>(), *clock)?;
+        clock_ports.push(port);
+    }
+
+    let print_ports: Vec<PortHandle> = args
+        .print
+        .iter()
+        .map(|name| sim.port(name))
+        .collect::<Result<_, _>>()?;
+
+    let mut vcd = match &args.vcd {
+        Some(file_name) => {
+            let time = match clock_ports.is_empty() || args.stimulus.is_some() {
+                true => VcdTime::Frame,
+                false => VcdTime::Time,
+            };
+            let mut vcd = VcdWriter::new(BufWriter::new(File::create(file_name)?), &sim, time)?;
+            vcd.sample(&sim)?;
+            Some(vcd)
+        }
+        None => None,
+    };
+
+    let mut after_frame = |sim: &mut Sim| -> Result<(), SimError> {
+        if let Some(vcd) = &mut vcd {
+            vcd.sample(sim)?;
+        }
+        if !print_ports.is_empty() {
+            let values: Vec<String> = print_ports
+                .iter()
+                .map(|port| format!("{} = {}", port.name(), format_value(sim, port, args.radix)))
+                .collect();
+            writeln!(
+                out,
+                "frame {}, time {}: {}",
+                sim.frame(),
+                sim.time(),
+                values.join(", ")
+            )?;
+        }
+        Ok(())
+    };
+
+    let mut passed = true;
+    match (&args.stimulus, clock_ports.first()) {
+        (Some(file_name), _) => {
+            let report = TestVectors::read_from_file(file_name)?.run_with(&mut sim, after_frame)?;
+            write!(out, "{}", report)?;
+            passed = report.passed();
+        }
+        (None, Some(clock_port)) => {
+            let mut result: Result<(), SimError> = Ok(());
+            let mut clock = sim.get_port(clock_port)[0];
+            let mut num_rising_edges = 0;
+            sim.run_until(
+                |sim| {
+                    result = after_frame(sim);
+                    let next_clock = sim.get_port(clock_port)[0];
+                    if clock == Logic::_0 && next_clock == Logic::_1 {
+                        num_rising_edges += 1;
+                    }
+                    clock = next_clock;
+                    result.is_err() || num_rising_edges >= args.cycles
+                },
+                usize::MAX,
+            )?;
+            result?;
+        }
+        (None, None) => {
+            for _ in 0..args.cycles {
+                sim.simulate()?;
+                after_frame(&mut sim)?;
+            }
+        }
+    }
+
+    if let Some(vcd) = vcd {
+        vcd.into_inner()?.flush()?;
+    }
+
+    for failure in sim.formal_failures() {
+        writeln!(out, "{}", failure)?;
+    }
+    passed &= sim.formal_failures().is_empty();
+
+    if args.print.is_empty() && args.stimulus.is_none() {
+        for info in module
+            .ports
+            .iter()
+            .filter(|it| it.direction == PortDirection::Output)
+        {
+            let port = sim.port(&info.name)?;
+            writeln!(
+                out,
+                "{} = {}",
+                info.name,
+                format_value(&mut sim, &port, args.radix)
+            )?;
+        }
+    }
+
+    Ok(passed)
+}
+
+fn select_top<'m>(modules: &'m [Module], top: Option<&str>) -> Result<&'m Module, CliError> {
+    match top {
+        Some(top) => modules
+            .iter()
+            .find(|it| it.name == top)
+            .ok_or_else(|| CliError::Usage(format!("there is no module [{}]", top))),
+        None if modules.len() == 1 => Ok(&modules[0]),
+        None => {
+            let mut names: Vec<&str> = modules.iter().map(|it| it.name.as_str()).collect();
+            names.sort();
+            Err(CliError::Usage(format!(
+                "choose one of the modules with --top: {}",
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+fn format_value(sim: &mut Sim, port: &PortHandle, radix: Radix) -> String {
+    sim.get_port(port).to_literal(radix)
+}
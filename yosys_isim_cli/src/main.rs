@@ -0,0 +1,18 @@
+use std::process::ExitCode;
+use yosys_isim_cli::CliError;
+
+/// exits with `1` if rows of the stimulus or formal cells failed and with `2` on errors
+fn main() -> ExitCode {
+    match yosys_isim_cli::run(std::env::args().skip(1), &mut std::io::stdout()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(CliError::Help) => {
+            println!("{}", CliError::Help);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use crate::CliError;
+use yosys_isim::json::parse_modules_from_file;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Clock;
+use yosys_isim::sim::HaltReason;
+use yosys_isim::sim::History;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::Radix;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::Watchpoint;
+
+/// The commands of the REPL with their arguments and what they do.
+pub const COMMANDS: &[(&str, &str, &str)] = &[
+    ("load", "<netlist.json>", "loads the modules of a netlist"),
+    ("modules", "", "lists the loaded modules"),
+    (
+        "top",
+        "<module>",
+        "simulates the module from its power-up state",
+    ),
+    ("ports", "", "lists the ports of the top module"),
+    (
+        "nets",
+        "[pattern]",
+        "lists the nets, `*` and `?` match any characters",
+    ),
+    (
+        "set",
+        "<port> <literal>",
+        "drives an in port, like `set a 8'h3f`",
+    ),
+    ("get", "<net>...", "prints nets and ports"),
+    (
+        "step",
+        "[n]",
+        "simulates n frames, stops early at watchpoints and failing formal cells",
+    ),
+    (
+        "clock",
+        "<port> [period [phase]]",
+        "drives an in port with a clock",
+    ),
+    (
+        "watch",
+        "<net> [literal]",
+        "stops when the net changes or becomes the literal",
+    ),
+    ("unwatch", "<id>", "removes a watchpoint"),
+    ("watches", "", "lists the watchpoints"),
+    ("force", "<net> <literal>", "keeps a net at the literal"),
+    ("release", "<net>", "releases a forced net"),
+    ("back", "[n]", "goes back n frames"),
+    ("reset", "", "goes back to the power-up state"),
+    ("radix", "b|o|d|h", "the radix of printed values"),
+    ("help", "", "prints this help"),
+    ("quit", "", "exits"),
+];
+
+/// What the REPL answers to a line.
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Output(String),
+    /// the modules of a netlist, the caller owns them and builds a new [Repl] on them
+    Load(Vec<Module>),
+    Quit,
+}
+
+/// The state of `yosys-isim-repl`, independent of the terminal, on modules owned by the caller.
+pub struct Repl<'m> {
+    modules: &'m [Module],
+    sim: Option<Sim<'m>>,
+    radix: Radix,
+}
+
+impl<'m> Repl<'m> {
+    pub fn new(modules: &'m [Module], radix: Radix) -> Repl<'m> {
+        Repl {
+            modules,
+            sim: None,
+            radix,
+        }
+    }
+
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<Reply, CliError> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((command, args)) = words.split_first() else {
+            return Ok(Reply::Output(String::new()));
+        };
+
+        let output = match (*command, args) {
+            ("load", [file_name]) => return Ok(Reply::Load(parse_modules_from_file(file_name)?)),
+            ("modules", []) => self.module_names().join("\n"),
+            ("top", [name]) => self.top(name)?,
+            ("ports", []) => {
+                let module = self.sim()?.design().module();
+                module
+                    .ports
+                    .iter()
+                    .map(|it| {
+                        format!(
+                            "{:<6} {} [{}]{}",
+                            it.direction.to_string(),
+                            it.name,
+                            it.width,
+                            if it.signed { " signed" } else { "" }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            ("nets", []) => self.net_names("*")?.join("\n"),
+            ("nets", [pattern]) => self.net_names(pattern)?.join("\n"),
+            ("set", [port, literal]) => {
+                let sim = self.sim_mut()?;
+                let port = sim.port(port)?;
+                let value = parse_literal(literal, port.width())?;
+                sim.set_port(&port, &value)?;
+                String::new()
+            }
+            ("get", [_, ..]) => args
+                .iter()
+                .map(|net| Ok(format!("{} = {}", net, self.get(net)?)))
+                .collect::<Result<Vec<_>, CliError>>()?
+                .join("\n"),
+            ("step", []) => self.step(1)?,
+            ("step", [n]) => self.step(parse_number(n)?)?,
+            ("clock", [port, clock @ ..]) if clock.len() <= 2 => {
+                let mut spec = Clock::new(10);
+                if let Some(period) = clock.first() {
+                    spec.period = parse_number(period)? as u64;
+                }
+                if let Some(phase) = clock.get(1) {
+                    spec.phase = parse_number(phase)? as u64;
+                }
+                let sim = self.sim_mut()?;
+                let port = sim.port(port)?;
+                if port.width() != 1 {
+                    return Err(CliError::Command(format!(
+                        "the clock [{}] is not 1 bit wide",
+                        port.name()
+                    )));
+                }
+                sim.add_clock(&port.to_in_port()?.into_width::<1>(), spec)?;
+                String::new()
+            }
+            ("watch", [net]) => {
+                let id = self.sim_mut()?.watch(Watchpoint::changes(net))?;
+                format!("watchpoint {}", id)
+            }
+            ("watch", [net, literal]) => {
+                let sim = self.sim_mut()?;
+                let value = parse_literal(literal, sim.net_width(net)?)?;
+                let id = sim.watch(Watchpoint::equals(net, &value))?;
+                format!("watchpoint {}", id)
+            }
+            ("unwatch", [id]) => {
+                let id = parse_number(id)?;
+                self.sim_mut()?.unwatch(id)?;
+                String::new()
+            }
+            ("watches", []) => {
+                let radix = self.radix;
+                self.sim()?
+                    .watchpoints()
+                    .map(|(id, watchpoint)| format!("{}: {}", id, describe(watchpoint, radix)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            ("force", [net, literal]) => {
+                let sim = self.sim_mut()?;
+                let value = parse_literal(literal, sim.net_width(net)?)?;
+                sim.force(net, &value)?;
+                String::new()
+            }
+            ("release", [net]) => {
+                self.sim_mut()?.release(net)?;
+                String::new()
+            }
+            ("back", []) => self.back(1)?,
+            ("back", [n]) => self.back(parse_number(n)?)?,
+            ("reset", []) => {
+                self.sim_mut()?.reset();
+                String::new()
+            }
+            ("radix", [radix]) => {
+                self.radix = match *radix {
+                    "b" => Radix::Bin,
+                    "o" => Radix::Oct,
+                    "d" => Radix::Dec,
+                    "h" => Radix::Hex,
+                    _ => return Err(CliError::Command(format!("unknown radix [{}]", radix))),
+                };
+                String::new()
+            }
+            ("help", []) => COMMANDS
+                .iter()
+                .map(|(command, args, help)| {
+                    format!("{:<32} {}", format!("{} {}", command, args), help)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ("quit" | "exit", []) => return Ok(Reply::Quit),
+            _ => match COMMANDS.iter().find(|it| it.0 == *command) {
+                Some((command, args, _)) => {
+                    let usage = format!("usage: {} {}", command, args);
+                    return Err(CliError::Command(usage.trim_end().to_string()));
+                }
+                None => {
+                    return Err(CliError::Command(format!(
+                        "unknown command [{}], try `help`",
+                        command
+                    )));
+                }
+            },
+        };
+
+        Ok(Reply::Output(output))
+    }
+
+    /// The start of the word at `pos` and the words it could be completed to.
+    /// File names after `load` are left to the terminal.
+    pub fn completions(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map_or(0, |it| it + 1);
+        let prefix = &line[start..pos];
+
+        let candidates: Vec<String> = match line[..start].split_whitespace().next() {
+            None => COMMANDS.iter().map(|it| it.0.to_string()).collect(),
+            Some("top") => self.module_names(),
+            Some("radix") => ["b", "o", "d", "h"].map(String::from).to_vec(),
+            Some("load" | "help" | "quit" | "exit") => Vec::new(),
+            Some(_) => self.net_names("*").unwrap_or_default(),
+        };
+
+        let candidates = candidates
+            .into_iter()
+            .filter(|it| it.starts_with(prefix))
+            .collect();
+        (start, candidates)
+    }
+
+    /// What to print after a `load` built this [Repl], a single module becomes the top.
+    pub fn loaded(&mut self) -> Result<String, CliError> {
+        let mut output = format!("loaded {} modules", self.modules.len());
+        if let [module] = self.modules {
+            output = format!("{}\n{}", output, self.top(&module.name)?);
+        }
+        Ok(output)
+    }
+
+    fn top(&mut self, name: &str) -> Result<String, CliError> {
+        let module = self
+            .modules
+            .iter()
+            .find(|it| it.name == name)
+            .ok_or_else(|| CliError::Command(format!("there is no module [{}]", name)))?;
+        self.sim = Some(Sim::try_with_config(
+            module,
+            SimConfig {
+                history: Some(History::default()),
+                stop_on_formal_failure: true,
+                ..Default::default()
+            },
+        )?);
+        Ok(format!("top {}", module.name))
+    }
+
+    fn step(&mut self, num_frames: usize) -> Result<String, CliError> {
+        let radix = self.radix;
+        let sim = self.sim_mut()?;
+        let halt = sim.run_until(|_| false, num_frames)?;
+
+        let mut lines = vec![format!("frame {}, time {}", halt.frame, sim.time())];
+        match halt.reason {
+            HaltReason::Watchpoints(hits) => {
+                for hit in hits {
+                    lines.push(format!(
+                        "watchpoint {} ({}): {} = {}",
+                        hit.id,
+                        describe(&hit.watchpoint, radix),
+                        hit.watchpoint.net(),
+                        LogicVec(hit.value).to_literal(radix)
+                    ));
+                }
+            }
+            HaltReason::FormalFailures(failures) => {
+                lines.extend(failures.iter().map(|it| it.to_string()));
+            }
+            _ => {}
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn back(&mut self, num_frames: usize) -> Result<String, CliError> {
+        let sim = self.sim_mut()?;
+        sim.step_back(num_frames)?;
+        Ok(format!("frame {}, time {}", sim.frame(), sim.time()))
+    }
+
+    fn get(&self, net: &str) -> Result<String, CliError> {
+        Ok(self.sim()?.get_net(net)?.to_literal(self.radix))
+    }
+
+    fn module_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.modules.iter().map(|it| it.name.clone()).collect();
+        names.sort();
+        names
+    }
+
+    fn net_names(&self, pattern: &str) -> Result<Vec<String>, CliError> {
+        Ok(self
+            .sim()?
+            .design()
+            .module()
+            .nets
+            .iter()
+            .map(|it| it.name.clone())
+            .filter(|it| glob_match(pattern.as_bytes(), it.as_bytes()))
+            .collect())
+    }
+
+    fn sim(&self) -> Result<&Sim<'m>, CliError> {
+        self.sim
+            .as_ref()
+            .ok_or_else(|| CliError::Command("no top module, try `load` and `top`".to_string()))
+    }
+
+    fn sim_mut(&mut self) -> Result<&mut Sim<'m>, CliError> {
+        self.sim
+            .as_mut()
+            .ok_or_else(|| CliError::Command("no top module, try `load` and `top`".to_string()))
+    }
+}
+
+fn describe(watchpoint: &Watchpoint, radix: Radix) -> String {
+    match watchpoint {
+        Watchpoint::Changes { net } => format!("{} changes", net),
+        Watchpoint::Equals { net, value } => {
+            format!("{} == {}", net, LogicVec(value.clone()).to_literal(radix))
+        }
+    }
+}
+
+fn parse_literal(literal: &str, width: usize) -> Result<LogicVec, CliError> {
+    let value: LogicVec = literal.parse()?;
+    if !value.fits(width) {
+        return Err(CliError::Command(format!(
+            "[{}] does not fit into [{}] bits",
+            literal, width
+        )));
+    }
+    Ok(value.resized(width))
+}
+
+fn parse_number(value: &str) -> Result<usize, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Command(format!("expected a number but got [{}]", value)))
+}
+
+/// `*` matches any characters, `?` a single one
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            glob_match(rest, name) || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name_rest))) => glob_match(rest, name_rest),
+        (Some((char, rest)), Some((name_char, name_rest))) => {
+            char == name_char && glob_match(rest, name_rest)
+        }
+        _ => false,
+    }
+}
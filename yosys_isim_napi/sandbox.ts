@@ -8,4 +8,5 @@ const sim = Sim.create(module);
 sim.set("a", [1])
 sim.set("b", [1])
 sim.simulate();
-console.log(sim.get("y", 1))
+console.log(sim.get("y"))
+console.log(module.ports())
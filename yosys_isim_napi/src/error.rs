@@ -1,17 +1,30 @@
 pub struct JsError {
+    code: &'static str,
     message: String,
 }
 
 impl From<yosys_isim::common::SimError> for JsError {
     fn from(value: yosys_isim::common::SimError) -> Self {
         JsError {
-            message: format!("{:?}", value),
+            code: value.code(),
+            message: value.to_string(),
         }
     }
 }
 
-impl From<JsError> for napi::JsError {
+// thrown by a JS callback
+impl From<napi::Error> for JsError {
+    fn from(value: napi::Error) -> Self {
+        JsError {
+            code: "JS_ERROR",
+            message: value.reason,
+        }
+    }
+}
+
+// the status of a napi error becomes the `code` property of the thrown JS error
+impl From<JsError> for napi::JsError<String> {
     fn from(value: JsError) -> Self {
-        napi::Error::new(napi::Status::GenericFailure, value.message).into()
+        napi::Error::new(value.code.to_string(), value.message).into()
     }
 }
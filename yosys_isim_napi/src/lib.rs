@@ -3,7 +3,9 @@ mod error;
 
 use error::*;
 use napi::Env;
+use napi::JsFunction;
 use napi_derive::napi;
+use yosys_isim::common::SimError;
 use yosys_isim::json;
 use yosys_isim::model;
 use yosys_isim::sim;
@@ -26,6 +28,32 @@ impl Module {
             })
             .collect())
     }
+
+    /// all in and out ports, sorted by name
+    #[napi]
+    pub fn ports(&self) -> Vec<PortInfo> {
+        unsafe { (*self.p_module).ports.iter().map(PortInfo::from).collect() }
+    }
+}
+
+#[napi(object)]
+pub struct PortInfo {
+    pub name: String,
+    #[napi(ts_type = "'input' | 'output'")]
+    pub direction: String,
+    pub width: u32,
+    pub signed: bool,
+}
+
+impl From<&model::PortInfo> for PortInfo {
+    fn from(value: &model::PortInfo) -> Self {
+        PortInfo {
+            name: value.name.clone(),
+            direction: value.direction.to_string(),
+            width: value.width as u32,
+            signed: value.signed,
+        }
+    }
 }
 
 #[napi]
@@ -54,33 +82,35 @@ impl Sim {
         #[napi(ts_arg_type = "[0 | 1]")] logics: Vec<i64>,
     ) -> Result<(), JsError> {
         unsafe {
-            let port = (*self.p_module).get_port_dynamic(
-                &(*self.p_module).in_ports,
-                &port_name,
-                logics.len(),
-            )?;
-
-            (*self.p_sim).set_dynamic(port, &logics);
+            let port = (*self.p_sim).port(&port_name)?;
+            (*self.p_sim).set_port(&port, &logics)?;
             Ok(())
         }
     }
 
-    #[napi(ts_return_type = "[0 | 1 | -1]")]
+    /// `width` is optional, if given it is checked against the width of the port
+    #[napi(ts_return_type = "(0 | 1 | 'X')[]")]
     pub fn get(
         &self,
         env: Env,
         port_name: String,
-        width: i64,
+        width: Option<i64>,
     ) -> Result<Vec<napi::JsUnknown>, JsError> {
         unsafe {
-            let port = (*self.p_module).get_port_dynamic(
-                &(*self.p_module).out_ports,
-                &port_name,
-                width as usize,
-            )?;
+            let port = (*self.p_sim).port(&port_name)?;
+            if let Some(width) = width.filter(|it| *it as usize != port.width()) {
+                return Err(SimError::PortWidthMismatch {
+                    module: port.module.clone(),
+                    cell: None,
+                    port: port_name,
+                    expected: width as usize,
+                    actual: port.width(),
+                }
+                .into());
+            }
             Ok((*self.p_sim)
-                .get_dynamic(port)
-                .into_iter()
+                .get_port(&port)
+                .iter()
                 .map(|logic| match logic {
                     sim::Logic::_0 => env.create_uint32(0).unwrap().into_unknown(),
                     sim::Logic::_1 => env.create_uint32(1).unwrap().into_unknown(),
@@ -90,6 +120,12 @@ impl Sim {
         }
     }
 
+    /// all in and out ports, sorted by name
+    #[napi]
+    pub fn ports(&self) -> Vec<PortInfo> {
+        unsafe { (*self.p_module).ports.iter().map(PortInfo::from).collect() }
+    }
+
     #[napi]
     pub fn simulate(&self) -> Result<(), JsError> {
         unsafe {
@@ -97,4 +133,105 @@ impl Sim {
             Ok(())
         }
     }
+
+    /// fires whenever the net changes, returns the id of the watchpoint
+    #[napi]
+    pub fn watch_changes(&self, net: String) -> Result<u32, JsError> {
+        unsafe { Ok((*self.p_sim).watch(sim::Watchpoint::Changes { net })? as u32) }
+    }
+
+    /// fires whenever the net becomes `logics`, returns the id of the watchpoint
+    #[napi]
+    pub fn watch_equals(
+        &self,
+        net: String,
+        #[napi(ts_arg_type = "[0 | 1]")] logics: Vec<i64>,
+    ) -> Result<u32, JsError> {
+        unsafe { Ok((*self.p_sim).watch(sim::Watchpoint::equals(&net, &logics))? as u32) }
+    }
+
+    #[napi]
+    pub fn unwatch(&self, id: u32) -> Result<(), JsError> {
+        unsafe {
+            (*self.p_sim).unwatch(id as usize)?;
+            Ok(())
+        }
+    }
+
+    /// Simulates up to `maxFrames` frames, until `predicate` returns true after a frame
+    /// or a watchpoint fires.
+    #[napi(ts_args_type = "predicate: () => boolean, maxFrames: number")]
+    pub fn run_until(&self, predicate: JsFunction, max_frames: u32) -> Result<Halt, JsError> {
+        for _ in 0..max_frames {
+            // the predicate may call back into this sim, so no borrow of it is held meanwhile
+            let halt = unsafe { (*self.p_sim).run_until(|_| false, 1)? };
+            if let sim::HaltReason::Watchpoints(_) = halt.reason {
+                return Ok(Halt::from(halt));
+            }
+            if predicate
+                .call_without_args(None)?
+                .coerce_to_bool()?
+                .get_value()?
+            {
+                return Ok(Halt::from(sim::Halt {
+                    frame: halt.frame,
+                    reason: sim::HaltReason::Predicate,
+                }));
+            }
+        }
+        unsafe {
+            Ok(Halt::from(sim::Halt {
+                frame: (*self.p_sim).frame(),
+                reason: sim::HaltReason::MaxFrames,
+            }))
+        }
+    }
+}
+
+#[napi(object)]
+pub struct WatchHit {
+    pub id: u32,
+    pub net: String,
+    /// MSB first, like `"01x"`
+    pub value: String,
+}
+
+#[napi(object)]
+pub struct Halt {
+    pub frame: u32,
+    #[napi(ts_type = "'predicate' | 'watchpoints' | 'formalFailures' | 'maxFrames'")]
+    pub reason: String,
+    pub hits: Vec<WatchHit>,
+}
+
+impl From<sim::Halt> for Halt {
+    fn from(value: sim::Halt) -> Self {
+        let (reason, hits) = match value.reason {
+            sim::HaltReason::Predicate => ("predicate", Vec::new()),
+            sim::HaltReason::Watchpoints(hits) => ("watchpoints", hits),
+            sim::HaltReason::FormalFailures(_) => ("formalFailures", Vec::new()),
+            sim::HaltReason::MaxFrames => ("maxFrames", Vec::new()),
+        };
+        Halt {
+            frame: value.frame as u32,
+            reason: reason.to_string(),
+            hits: hits
+                .into_iter()
+                .map(|hit| WatchHit {
+                    id: hit.id as u32,
+                    net: hit.watchpoint.net().to_string(),
+                    value: hit
+                        .value
+                        .iter()
+                        .rev()
+                        .map(|logic| match logic {
+                            sim::Logic::_0 => '0',
+                            sim::Logic::_1 => '1',
+                            sim::Logic::X => 'x',
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
 }
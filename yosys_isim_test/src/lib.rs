@@ -1,12 +1,71 @@
 use lazy_static::lazy_static;
 use util::compile_sv::compile;
+use util::compile_sv::compile_netlist;
+use yosys_isim::json::parse_modules_from_file;
 use yosys_isim::model::Module;
 
+#[cfg(test)]
 mod test_bit_mem;
+#[cfg(test)]
+mod test_cli;
+#[cfg(test)]
+mod test_clock;
+#[cfg(test)]
+mod test_design;
+#[cfg(test)]
+mod test_equiv;
+#[cfg(test)]
+mod test_errors;
+#[cfg(test)]
+mod test_exhaustive;
+#[cfg(test)]
+mod test_force;
+#[cfg(test)]
+mod test_formal;
+#[cfg(test)]
 mod test_gates;
+#[cfg(test)]
+mod test_history;
+#[cfg(test)]
+mod test_int;
+#[cfg(test)]
+mod test_jit;
+#[cfg(test)]
+mod test_lanes;
+#[cfg(test)]
+mod test_logic_vec;
+#[cfg(test)]
+mod test_partition;
+#[cfg(test)]
+mod test_ports;
+#[cfg(test)]
+mod test_property;
+#[cfg(test)]
+mod test_repl;
+#[cfg(test)]
+mod test_reset;
+#[cfg(test)]
+mod test_scheduler;
+#[cfg(test)]
+mod test_snapshot;
+#[cfg(test)]
+mod test_tape;
+#[cfg(test)]
+mod test_vectors;
+#[cfg(test)]
+mod test_watch;
+#[cfg(test)]
+mod test_x_policy;
 mod util;
 
 lazy_static! {
-    pub static ref TEST_GATES_SV: Vec<Module> = compile("src/test_gates.sv");
+    pub static ref TEST_GATES_NETLIST: String = compile_netlist("src/test_gates.sv").unwrap();
+    pub static ref TEST_GATES_SV: Vec<Module> =
+        parse_modules_from_file(&TEST_GATES_NETLIST).unwrap();
     pub static ref TEST_BIT_MEM: Vec<Module> = compile("src/test_bit_mem.sv");
+    pub static ref TEST_UNSUPPORTED_NETLIST: String =
+        compile_netlist("src/test_unsupported.sv").unwrap();
+    pub static ref TEST_FORMAL_NETLIST: String = compile_netlist("src/test_formal.sv").unwrap();
+    pub static ref TEST_FORMAL_SV: Vec<Module> =
+        parse_modules_from_file(&TEST_FORMAL_NETLIST).unwrap();
 }
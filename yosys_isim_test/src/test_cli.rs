@@ -0,0 +1,108 @@
+use crate::TEST_GATES_NETLIST;
+use std::fs;
+use std::ops::Deref;
+use yosys_isim_cli::CliError;
+use yosys_isim_cli::run;
+
+pub fn run_cli(args: &[&str]) -> Result<(bool, String), CliError> {
+    let mut out: Vec<u8> = Vec::new();
+    let args = std::iter::once(TEST_GATES_NETLIST.deref().as_str())
+        .chain(args.iter().copied())
+        .map(|it| it.to_string());
+    let passed = run(args, &mut out)?;
+    Ok((passed, String::from_utf8(out).unwrap()))
+}
+
+#[test]
+pub fn test_cli_clock() {
+    (|| -> Result<(), CliError> {
+        let (_, out) = run_cli(&[
+            "--top", "Shift", "--clock", "c:10", "--set", "d=1", "--cycles", "3", "--print", "q",
+            "--radix", "b",
+        ])?;
+        assert_eq!(
+            out,
+            "frame 1, time 5: q = 3'bxxx\n\
+             frame 2, time 10: q = 3'bxx1\n\
+             frame 3, time 15: q = 3'bxx1\n\
+             frame 4, time 20: q = 3'bx11\n\
+             frame 5, time 25: q = 3'bx11\n\
+             frame 6, time 30: q = 3'b111\n"
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_cli_vcd() {
+    (|| -> Result<(), CliError> {
+        fs::create_dir_all("target")?;
+        let vcd_file = "target/test_cli_vcd.vcd";
+        run_cli(&[
+            "--top", "Shift", "--clock", "c", "--set", "d=1", "--cycles", "2", "--vcd", vcd_file,
+        ])?;
+
+        let vcd = fs::read_to_string(vcd_file)?;
+        assert!(vcd.contains("$scope module Shift $end\n"), "{}", vcd);
+        assert!(vcd.contains("$var wire 3 # q $end\n"), "{}", vcd);
+        assert!(
+            vcd.contains("#0\n$dumpvars\n1!\n1\"\nbxxx #\n$end\n#5\n0!\n#10\n1!\nbxx1 #\n"),
+            "{}",
+            vcd
+        );
+        assert!(vcd.ends_with("#20\n1!\nbx11 #\n"), "{}", vcd);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_cli_stimulus() {
+    (|| -> Result<(), CliError> {
+        let (passed, out) = run_cli(&["--top", "Dff", "--stimulus", "src/test_vectors.csv"])?;
+        assert!(passed);
+        assert_eq!(out, "6 of 6 rows passed\n");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_cli_print_outputs() {
+    (|| -> Result<(), CliError> {
+        let (_, out) = run_cli(&["--top", "Add", "--set", "a=3", "--set", "b=8'h04"])?;
+        assert_eq!(out, "y = 8'h07\n");
+
+        let (_, out) = run_cli(&["--top", "Ports", "--set", "addr=255", "--radix", "d"])?;
+        assert_eq!(out, "data = 8'd255\nflags = 2'dx\n");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_cli_errors() {
+    let err = run_cli(&[]).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("choose one of the modules with --top: ")
+    );
+
+    let err = run_cli(&["--top", "Add", "--frobnicate"]).unwrap_err();
+    assert!(matches!(err, CliError::Usage(_)));
+
+    let err = run_cli(&["--top", "Add", "--clock", "nope"]).unwrap_err();
+    assert!(matches!(err, CliError::Sim(_)));
+
+    let err = run_cli(&["--top", "Add", "--set", "a=300"]).unwrap_err();
+    assert!(matches!(err, CliError::Usage(_)));
+    assert!(
+        err.to_string()
+            .starts_with("[300] does not fit into the width [8] of port [a]")
+    );
+}
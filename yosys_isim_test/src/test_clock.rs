@@ -0,0 +1,126 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Clock;
+use yosys_isim::sim::History;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_clock_edges() {
+    let clock = Clock {
+        period: 10,
+        phase: 5,
+        duty_cycle: 0.5,
+    };
+    let values: Vec<Logic> = [0, 4, 5, 9, 10, 14, 15]
+        .map(|it| clock.value_at(it))
+        .to_vec();
+    assert_eq!(values, [0, 0, 1, 1, 0, 0, 1].map(Logic::from).to_vec());
+    let edges: Vec<u64> = [0, 5, 7, 10, 15]
+        .map(|it| clock.next_edge_after(it))
+        .to_vec();
+    assert_eq!(edges, [5, 10, 10, 15, 20]);
+
+    let clock = Clock {
+        duty_cycle: 0.2,
+        ..Clock::new(15)
+    };
+    assert_eq!(clock.high_time(), 3);
+    assert_eq!(clock.value_at(0), Logic::_1);
+    assert_eq!(clock.next_edge_after(0), 3);
+    assert_eq!(clock.next_edge_after(3), 15);
+}
+
+#[test]
+pub fn test_run_cycles() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<1>("d")?;
+        let port_q = module.get_out_port::<3>("q")?;
+        let mut sim = Sim::new(module);
+
+        sim.add_clock(
+            &port_c,
+            Clock {
+                phase: 5,
+                ..Clock::new(10)
+            },
+        )?;
+        sim.set(&port_d, [1]);
+        sim.run_cycles(&port_c, 3)?;
+
+        assert_eq!(sim.time(), 25);
+        assert_eq!(sim.frame(), 5);
+        assert_eq!(sim.get(&port_q), [Logic::_1; 3]);
+
+        let err = sim.run_cycles(&port_d, 1).unwrap_err();
+        assert_eq!(err.code(), "NOT_FOUND");
+
+        let err = sim.add_clock(&port_c, Clock::new(1)).unwrap_err();
+        assert_eq!(err.code(), "ILLEGAL_CLOCK");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_two_clocks() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("TwoClocks")?;
+
+        let port_c0 = module.get_in_port::<1>("c0")?;
+        let port_c1 = module.get_in_port::<1>("c1")?;
+        let port_d = module.get_in_port::<1>("d")?;
+        let port_q0 = module.get_out_port::<1>("q0")?;
+        let port_q1 = module.get_out_port::<1>("q1")?;
+        let mut sim = Sim::with_config(
+            module,
+            SimConfig {
+                history: Some(History::default()),
+                ..Default::default()
+            },
+        );
+
+        // c0 rises at 5, 15, 25 and falls at 10, 20, 30
+        // c1 rises at 15, 30 and falls at 8, 23
+        sim.add_clock(
+            &port_c0,
+            Clock {
+                phase: 5,
+                ..Clock::new(10)
+            },
+        )?;
+        sim.add_clock(&port_c1, Clock::new(15))?;
+
+        sim.set(&port_d, [1]);
+        sim.run_for(14)?;
+        assert_eq!(sim.frame(), 3);
+        assert_eq!(sim.get(&port_q0), [Logic::_1]);
+        assert_eq!(sim.get(&port_q1), [Logic::X]);
+
+        // both clocks rise at 15, c1 samples q0 from before the edge of c0
+        sim.set(&port_d, [0]);
+        sim.run_for(15)?;
+        assert_eq!(sim.frame(), 7);
+        assert_eq!(sim.time(), 29);
+        assert_eq!(sim.get(&port_q0), [Logic::_0]);
+        assert_eq!(sim.get(&port_q1), [Logic::_1]);
+
+        sim.run_for(1)?;
+        assert_eq!(sim.frame(), 8);
+        assert_eq!(sim.get(&port_q1), [Logic::_0]);
+
+        sim.goto_frame(3)?;
+        assert_eq!(sim.time(), 10);
+        assert_eq!(sim.get(&port_q0), [Logic::_1]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
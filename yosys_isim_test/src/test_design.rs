@@ -0,0 +1,120 @@
+use crate::TEST_GATES_SV;
+use crate::util::random::Stimulus;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::thread;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::Random;
+use yosys_isim::common::SimError;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Design;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::Parallel;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+const NUM_THREADS: u64 = 4;
+
+#[test]
+pub fn test_spawned_sims_are_independent() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Dff")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<2>("d")?;
+        let port_q = module.get_out_port::<2>("q")?;
+
+        let design = Arc::new(Design::new(module));
+        let mut sim = design.spawn();
+        sim.set(&port_c, [0]);
+        sim.set(&port_d, [1, 0]);
+        sim.simulate()?;
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+        assert_eq!(sim.get(&port_q), [Logic::_1, Logic::_0]);
+
+        let mut other = design.spawn();
+        assert_eq!(other.frame(), 0);
+        assert_eq!(other.get(&port_q), [Logic::X; 2]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_threads_match_serial() {
+    let configs = [
+        SimConfig::default(),
+        SimConfig {
+            engine: Engine::Tape,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Jit,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Tape,
+            parallel: Some(Parallel {
+                num_threads: 2,
+                min_cells: 0,
+            }),
+            ..Default::default()
+        },
+    ];
+
+    for module in TEST_GATES_SV.deref().iter() {
+        for config in configs.iter() {
+            do_test_threads(module, config.clone()).unwrap();
+        }
+    }
+}
+
+/// runs one [Sim] per thread, all spawned from the same [Design],
+/// each with its own stimulus, and compares them to a [Sim] of their own
+pub fn do_test_threads(module: &Module, config: SimConfig) -> Result<(), SimError> {
+    let design = Arc::new(Design::with_config(module, config.clone()));
+
+    let traces: Vec<Vec<Vec<Logic>>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..NUM_THREADS)
+            .map(|seed| {
+                let mut sim = design.spawn();
+                scope.spawn(move || run(module, &mut sim, seed))
+            })
+            .collect();
+        handles.into_iter().map(|it| it.join().unwrap()).collect()
+    });
+
+    for (seed, trace) in traces.into_iter().enumerate() {
+        let mut serial = Sim::with_config(module, config.clone());
+        let expected = run(module, &mut serial, seed as u64);
+        assert_eq!(
+            trace, expected,
+            "{} differs for seed {} with {:?}",
+            module.name, seed, config
+        );
+    }
+
+    Ok(())
+}
+
+/// the values of all out ports after each of 64 frames of random stimulus
+fn run(module: &Module, sim: &mut Sim, seed: u64) -> Vec<Vec<Logic>> {
+    let mut random = Random::new(0x0123_4567_89ab_cdef ^ seed);
+    let mut trace = Vec::new();
+    for _ in 0..64 {
+        for port in module.in_ports.iter() {
+            let logics = random.bits(port.wires.len());
+            sim.set_dynamic(port, &logics);
+        }
+
+        sim.simulate().unwrap();
+
+        for port in module.out_ports.iter() {
+            trace.push(sim.get_dynamic(port));
+        }
+    }
+    trace
+}
@@ -0,0 +1,103 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::RegisterInit;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::verify::CoSim;
+use yosys_isim::verify::Stimulus;
+
+pub fn co_sim(reference: &str, candidate: &str) -> Result<CoSim<'static>, SimError> {
+    let modules = TEST_GATES_SV.deref();
+    CoSim::new(
+        Sim::new(modules.iter().find_by_name(reference)?),
+        Sim::new(modules.iter().find_by_name(candidate)?),
+    )
+}
+
+#[test]
+pub fn test_equiv_exhaustive() {
+    (|| -> Result<(), SimError> {
+        let report = co_sim("And", "AndDeMorgan")?.run(Stimulus::Exhaustive)?;
+        assert!(report.passed());
+        assert_eq!(report.num_frames, 4);
+        assert_eq!(report.to_string(), "equivalent in 4 frames\n");
+
+        let report = co_sim("Add", "Add")?.run(Stimulus::Random {
+            seed: 7,
+            num_frames: 100,
+        })?;
+        assert!(report.passed());
+        assert_eq!(report.num_frames, 100);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_equiv_divergence() {
+    (|| -> Result<(), SimError> {
+        let report = co_sim("And", "Nand")?.run(Stimulus::Exhaustive)?;
+        let divergence = report.divergence.as_ref().unwrap();
+        assert_eq!(report.num_frames, 1);
+        assert_eq!(divergence.frame, 1);
+        assert_eq!(divergence.port, "y");
+        assert_eq!(divergence.reference, LogicVec::from([Logic::_0]));
+        assert_eq!(divergence.candidate, LogicVec::from([Logic::_1]));
+        assert_eq!(
+            report.to_string(),
+            "diverged in frame 1: y = 1'b1, expected 1'b0 (a = 1'b0, b = 1'b0)\n"
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_equiv_x_is_dont_care() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Pipeline")?;
+        let zero = SimConfig {
+            register_init: RegisterInit::Zero,
+            ..Default::default()
+        };
+        let stimulus = Stimulus::Random {
+            seed: 1,
+            num_frames: 50,
+        };
+
+        // the reference starts with `q = X`, which matches anything
+        let report =
+            CoSim::new(Sim::new(module), Sim::with_config(module, zero.clone()))?.run(stimulus)?;
+        assert!(report.passed());
+
+        // the candidate starts with `q = X`, which only matches `X`
+        let report = CoSim::new(Sim::with_config(module, zero), Sim::new(module))?.run(stimulus)?;
+        let divergence = report.divergence.unwrap();
+        assert_eq!(divergence.port, "y");
+        assert!(divergence.candidate.contains(&Logic::X));
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_equiv_errors() {
+    let err = co_sim("And", "Add").err().unwrap();
+    assert_eq!(err.code(), "PORT_WIDTH_MISMATCH");
+
+    let err = co_sim("And", "Dff").err().unwrap();
+    assert_eq!(err.code(), "PORT_NOT_FOUND");
+
+    let err = co_sim("And32", "And32")
+        .unwrap()
+        .run(Stimulus::Exhaustive)
+        .unwrap_err();
+    assert_eq!(err.code(), "TOO_MANY_INPUT_BITS");
+}
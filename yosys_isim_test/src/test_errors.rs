@@ -0,0 +1,82 @@
+use crate::TEST_GATES_SV;
+use crate::TEST_UNSUPPORTED_NETLIST;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::json::parse_modules_from_file;
+use yosys_isim::json::parse_modules_from_file_checked;
+
+#[test]
+pub fn test_port_not_found() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("And")?;
+
+        match module.get_in_port::<1>("c") {
+            Err(SimError::PortNotFound { module, cell, port }) => {
+                assert_eq!(module, "And");
+                assert_eq!(cell, None);
+                assert_eq!(port, "c");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_port_width_mismatch() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("And32")?;
+
+        let err = module.get_out_port::<8>("y").unwrap_err();
+        assert_eq!(err.code(), "PORT_WIDTH_MISMATCH");
+        match err {
+            SimError::PortWidthMismatch {
+                port,
+                expected,
+                actual,
+                ..
+            } => {
+                assert_eq!(port, "y");
+                assert_eq!(expected, 8);
+                assert_eq!(actual, 32);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_unsupported_cell_type() {
+    match parse_modules_from_file(&TEST_UNSUPPORTED_NETLIST) {
+        Err(SimError::UnsupportedCellType {
+            module, cell_type, ..
+        }) => {
+            assert!(["Xor", "Xor3", "Mux"].contains(&module.as_str()));
+            assert!(["$xor", "$mux"].contains(&cell_type.as_str()));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+pub fn test_report_all_unsupported() {
+    let report = match parse_modules_from_file_checked(&TEST_UNSUPPORTED_NETLIST) {
+        Err(SimError::UnsupportedNetlist { report }) => report,
+        other => panic!("unexpected {:?}", other),
+    };
+
+    let cell_types: Vec<(&str, usize)> = report
+        .unsupported_cell_types
+        .iter()
+        .map(|it| (it.cell_type.as_str(), it.count))
+        .collect();
+    assert_eq!(cell_types, [("$mux", 1), ("$xor", 3)]);
+    assert!(report.unsupported_cell_types[1].examples[0].starts_with("Xor"));
+    assert!(report.unknown_parameters.is_empty());
+}
@@ -0,0 +1,122 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::IntValue;
+use yosys_isim::sim::Sim;
+use yosys_isim::verify::Exhaustive;
+use yosys_isim::verify::MAX_EXHAUSTIVE_BITS;
+use yosys_isim::verify::MAX_REPORTED_MISMATCHES;
+use yosys_isim::verify::TestVectors;
+
+#[test]
+pub fn test_exhaustive_check() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+        let exhaustive = Exhaustive::new(module)?;
+        assert_eq!(exhaustive.num_combinations(), 1 << 16);
+        assert_eq!(exhaustive.inputs(0x0201), [1, 2]);
+
+        let report = exhaustive.check(|inputs| vec![inputs[0] + inputs[1]])?;
+        assert!(report.passed(), "{}", report);
+
+        let report = exhaustive.check(|inputs| vec![inputs[0] | inputs[1]])?;
+        assert!(!report.passed());
+        assert_eq!(report.mismatches[0].combination, 0x0101);
+        // `a + b == a | b` only if no bit is set in both
+        assert_eq!(report.num_failed, (1 << 16) - 6561);
+        assert_eq!(report.mismatches.len(), MAX_REPORTED_MISMATCHES);
+        assert!(report.to_string().starts_with(
+            "6561 of 65536 combinations passed\n  \
+             a = 8'b00000001, b = 8'b00000001: y = 8'b00000010, expected 8'b00000001\n"
+        ));
+
+        // one value per out port
+        let err = exhaustive.check(|_| vec![]).unwrap_err();
+        assert_eq!(err.code(), "EXPECTED_LENGTH_MISMATCH");
+        let err = exhaustive
+            .check(|inputs| vec![inputs[0], inputs[1]])
+            .unwrap_err();
+        assert_eq!(err.code(), "EXPECTED_LENGTH_MISMATCH");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_exhaustive_max_bits() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add12")?;
+        let exhaustive = Exhaustive::new(module)?;
+        assert_eq!(exhaustive.num_combinations(), 1 << MAX_EXHAUSTIVE_BITS);
+
+        let report = exhaustive.check(|inputs| vec![inputs[0] + inputs[1]])?;
+        assert!(report.passed(), "{}", report);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_exhaustive_binary_ops() {
+    do_test_exhaustive_binary_op("And", |a, b| a & b).unwrap();
+    do_test_exhaustive_binary_op("Or", |a, b| a | b).unwrap();
+    do_test_exhaustive_binary_op("Nand", |a, b| !(a & b)).unwrap();
+    do_test_exhaustive_binary_op("Nor", |a, b| !(a | b)).unwrap();
+}
+
+pub fn do_test_exhaustive_binary_op(
+    module_name: &str,
+    eval: fn(u64, u64) -> u64,
+) -> Result<(), SimError> {
+    let module = TEST_GATES_SV.deref().iter().find_by_name(module_name)?;
+    let report = Exhaustive::new(module)?.check(|inputs| vec![eval(inputs[0], inputs[1])])?;
+    assert!(report.passed(), "{}: {}", module_name, report);
+    Ok(())
+}
+
+#[test]
+pub fn test_exhaustive_truth_table() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Nand")?;
+        let table = Exhaustive::new(module)?.truth_table()?;
+        assert_eq!(table.row(3), [IntValue::Known(0)]);
+        assert_eq!(
+            table.to_string(),
+            "a, b, y\n\
+             1'h0, 1'h0, 1'h1\n\
+             1'h1, 1'h0, 1'h1\n\
+             1'h0, 1'h1, 1'h1\n\
+             1'h1, 1'h1, 1'h0\n"
+        );
+
+        // the table is a test vector table
+        let report = TestVectors::parse(&table.to_string())?.run(&mut Sim::new(module))?;
+        assert!(report.passed(), "{}", report);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_exhaustive_errors() {
+    let modules = TEST_GATES_SV.deref();
+
+    let err = Exhaustive::new(modules.iter().find_by_name("Dff").unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "NOT_COMBINATIONAL");
+
+    let err = Exhaustive::new(modules.iter().find_by_name("And32").unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "TOO_MANY_INPUT_BITS");
+
+    let err = Exhaustive::new(modules.iter().find_by_name("Sink").unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "NO_OUT_PORTS");
+}
@@ -0,0 +1,139 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::CellSimModel;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::Parallel;
+use yosys_isim::sim::Scheduler;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+pub fn configs() -> [SimConfig; 5] {
+    [
+        SimConfig::default(),
+        SimConfig {
+            scheduler: Scheduler::EventDriven,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Tape,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Jit,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Tape,
+            parallel: Some(Parallel {
+                num_threads: 2,
+                min_cells: 0,
+            }),
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+pub fn test_force_combinational() {
+    for config in configs() {
+        do_test_force_combinational(config).unwrap();
+    }
+}
+
+pub fn do_test_force_combinational(config: SimConfig) -> Result<(), SimError> {
+    let module = TEST_GATES_SV.deref().iter().find_by_name("And")?;
+
+    let port_a = module.get_in_port::<1>("a")?;
+    let port_b = module.get_in_port::<1>("b")?;
+    let port_y = module.get_out_port::<1>("y")?;
+    let mut sim = Sim::with_config(module, config);
+
+    sim.set(&port_a, [0]);
+    sim.set(&port_b, [1]);
+    sim.force("y", &[1])?;
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [Logic::_1]);
+
+    sim.set(&port_a, [1]);
+    sim.set(&port_b, [0]);
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [Logic::_1]);
+
+    sim.release("y")?;
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [Logic::_0]);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_force_register() {
+    for config in configs() {
+        do_test_force_register(config).unwrap();
+    }
+}
+
+pub fn do_test_force_register(config: SimConfig) -> Result<(), SimError> {
+    let module = TEST_GATES_SV.deref().iter().find_by_name("Pipeline")?;
+
+    let port_c = module.get_in_port::<1>("c")?;
+    let port_a = module.get_in_port::<4>("a")?;
+    let port_b = module.get_in_port::<4>("b")?;
+    let port_y = module.get_out_port::<4>("y")?;
+    let mut sim = Sim::with_config(module, config);
+
+    let clock = |sim: &mut Sim| -> Result<[Logic; 4], SimError> {
+        sim.set(&port_c, [0]);
+        sim.simulate()?;
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+        Ok(sim.get(&port_y))
+    };
+
+    use Logic::_0;
+    use Logic::_1;
+
+    sim.set(&port_a, [0, 0, 0, 0]);
+    sim.set(&port_b, [0, 0, 0, 0]);
+    sim.force("q", &[1, 0, 1, 0])?;
+    assert_eq!(clock(&mut sim)?, [_1, _0, _1, _0]);
+
+    // a released register keeps its value until it is clocked
+    sim.release("q")?;
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [_1, _0, _1, _0]);
+    assert_eq!(clock(&mut sim)?, [_0, _0, _0, _0]);
+
+    sim.deposit("q", &[0, 1, 1, 0])?;
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [_0, _1, _1, _0]);
+    assert_eq!(clock(&mut sim)?, [_0, _0, _0, _0]);
+
+    // registers can be found by the name of their cell as well
+    let dff = module.cells.iter().find(|it| it.is_sequential()).unwrap();
+    sim.deposit(dff.name(), &[1, 1, 1, 1])?;
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_y), [_1, _1, _1, _1]);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_force_errors() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Pipeline")?;
+        let mut sim = Sim::new(module);
+
+        let err = sim.force("nope", &[1]).unwrap_err();
+        assert_eq!(err.code(), "NOT_FOUND");
+
+        let err = sim.deposit("q", &[1, 0]).unwrap_err();
+        assert_eq!(err.code(), "NET_WIDTH_MISMATCH");
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,186 @@
+use crate::TEST_FORMAL_NETLIST;
+use crate::TEST_FORMAL_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::json::parse_modules_from_file_checked;
+use yosys_isim::model::FormalKind;
+use yosys_isim::sim::Clock;
+use yosys_isim::sim::Edge;
+use yosys_isim::sim::FormalEvent;
+use yosys_isim::sim::HaltReason;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_formal_cells() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_FORMAL_SV.deref().iter().find_by_name("Formal")?;
+        assert_eq!(module.cells.len(), 4);
+
+        let cells: Vec<(&str, FormalKind)> = module
+            .formal_cells
+            .iter()
+            .map(|it| (it.name.as_str(), it.kind))
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                ("a_implies_b", FormalKind::Assert),
+                ("a_or_b", FormalKind::Assert),
+                ("both", FormalKind::Cover),
+            ]
+        );
+
+        let a_or_b = module.formal_cells.iter().find_by_name("a_or_b")?;
+        assert!(a_or_b.port_trg.is_some());
+        assert_eq!(a_or_b.polarity, Edge::POSITIVE);
+        assert_eq!(a_or_b.src.as_deref(), Some("src/test_formal.sv:10.3-10.48"));
+
+        let a_implies_b = module.formal_cells.iter().find_by_name("a_implies_b")?;
+        assert!(a_implies_b.port_trg.is_none());
+
+        // the parameters of `$check` are known
+        parse_modules_from_file_checked(&TEST_FORMAL_NETLIST)?;
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_formal_immediate() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_FORMAL_SV.deref().iter().find_by_name("Formal")?;
+        let port_a = module.get_in_port::<1>("a")?;
+        let port_b = module.get_in_port::<1>("b")?;
+        let port_c = module.get_in_port::<1>("c")?;
+
+        let mut sim = Sim::new(module);
+        sim.set(&port_c, [0]);
+
+        sim.set(&port_a, [1]);
+        sim.set(&port_b, [0]);
+        sim.simulate()?;
+        assert_eq!(
+            sim.formal_failures(),
+            [FormalEvent {
+                frame: 1,
+                time: 0,
+                cell: "a_implies_b".to_string(),
+                kind: FormalKind::Assert,
+                src: Some("src/test_formal.sv:8.3-8.41".to_string()),
+            }]
+        );
+        assert_eq!(
+            sim.formal_failures()[0].to_string(),
+            "assert [a_implies_b] failed in frame 1 at time 0 (src/test_formal.sv:8.3-8.41)"
+        );
+
+        // `a` implies `b` again
+        sim.set(&port_a, [0]);
+        sim.simulate()?;
+        sim.set(&port_a, [1]);
+        sim.set(&port_b, [1]);
+        sim.simulate()?;
+        sim.simulate()?;
+        assert_eq!(sim.formal_failures().len(), 1);
+        assert_eq!(sim.cover_count("both")?, 2);
+
+        let counts: Vec<(&str, usize)> = sim
+            .formal_counts()
+            .map(|(cell, count)| (cell.name.as_str(), count))
+            .collect();
+        assert_eq!(counts, [("a_implies_b", 1), ("a_or_b", 0), ("both", 2)]);
+
+        sim.reset();
+        assert!(sim.formal_failures().is_empty());
+        assert_eq!(sim.cover_count("both")?, 0);
+
+        assert_eq!(sim.cover_count("a_or_b").unwrap_err().code(), "NOT_FOUND");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_formal_clocked() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_FORMAL_SV.deref().iter().find_by_name("Formal")?;
+        let port_a = module.get_in_port::<1>("a")?;
+        let port_b = module.get_in_port::<1>("b")?;
+        let port_c = module.get_in_port::<1>("c")?;
+
+        let mut sim = Sim::new(module);
+        sim.set(&port_a, [0]);
+        sim.set(&port_b, [0]);
+        sim.set(&port_c, [0]);
+        sim.simulate()?;
+        assert!(sim.formal_failures().is_empty());
+
+        // `a | b` is sampled before the rising edge
+        sim.set(&port_a, [1]);
+        sim.set(&port_b, [1]);
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+        let cells: Vec<&str> = sim
+            .formal_failures()
+            .iter()
+            .map(|it| it.cell.as_str())
+            .collect();
+        assert_eq!(cells, ["a_or_b"]);
+
+        // no edge, no check
+        sim.set(&port_a, [0]);
+        sim.set(&port_b, [0]);
+        sim.simulate()?;
+        sim.set(&port_c, [0]);
+        sim.simulate()?;
+        assert_eq!(sim.formal_failures().len(), 1);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_formal_stop_on_failure() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_FORMAL_SV.deref().iter().find_by_name("Formal")?;
+        let port_a = module.get_in_port::<1>("a")?;
+        let port_c = module.get_in_port::<1>("c")?;
+
+        for stop_on_formal_failure in [false, true] {
+            let mut sim = Sim::with_config(
+                module,
+                SimConfig {
+                    stop_on_formal_failure,
+                    ..Default::default()
+                },
+            );
+            sim.add_clock(&port_c, Clock::new(10))?;
+            sim.set(&port_a, [0]);
+
+            // `b` is `X`, so `a | b` fails on the first rising edge at 10
+            let halt = sim.run_for(100)?;
+            if !stop_on_formal_failure {
+                assert_eq!(halt, None);
+                assert_eq!(sim.formal_failures().len(), 10);
+                continue;
+            }
+
+            let halt = halt.unwrap();
+            assert_eq!(halt.frame, sim.frame());
+            assert_eq!(sim.time(), 10);
+            let HaltReason::FormalFailures(failures) = halt.reason else {
+                panic!("{:?}", halt.reason);
+            };
+            assert_eq!(failures, sim.formal_failures());
+            assert_eq!(failures[0].cell, "a_or_b");
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
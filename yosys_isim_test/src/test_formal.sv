@@ -0,0 +1,11 @@
+module Formal (
+    input  logic c,
+    input  logic a,
+    input  logic b,
+    output logic y
+);
+  assign y = a & b;
+  a_implies_b: assert property (~a | b);
+  both: cover property (y);
+  a_or_b: assert property (@(posedge c) a | b);
+endmodule
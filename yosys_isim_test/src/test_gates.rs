@@ -3,6 +3,7 @@ use crate::util::assert::assert;
 use std::ops::Deref;
 use yosys_isim::common::FindByName;
 use yosys_isim::common::SimError;
+use yosys_isim::sim::IntValue;
 use yosys_isim::sim::Logic;
 use yosys_isim::sim::Sim;
 
@@ -43,12 +44,12 @@ pub fn do_test_binary_op<const L: usize>(
 ) -> Result<(), SimError> {
     let module = TEST_GATES_SV.deref().iter().find_by_name(module_name)?;
 
-    for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
-        let port_a = module.get_in_port::<L>("a")?;
-        let port_b = module.get_in_port::<L>("b")?;
-        let port_y = module.get_out_port::<L>("y")?;
-        let mut sim = Sim::new(&module);
+    let port_a = module.get_in_port::<L>("a")?;
+    let port_b = module.get_in_port::<L>("b")?;
+    let port_y = module.get_out_port::<L>("y")?;
+    let mut sim = Sim::new(module);
 
+    for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
         sim.set(&port_a, [a; L]);
         sim.set(&port_b, [b; L]);
         sim.simulate()?;
@@ -68,29 +69,33 @@ pub fn test_dff() {
         let port_c = module.get_in_port::<1>("c")?;
         let port_d = module.get_in_port::<2>("d")?;
         let port_q = module.get_out_port::<2>("q")?;
-        let mut sim: Sim<'_> = Sim::new(&module);
+        let mut sim: Sim<'_> = Sim::new(module);
 
-        assert_eq!(sim.get(&port_q), [Logic::X; 2]);
+        let unknown = IntValue::Unknown {
+            value: 0,
+            x_mask: 0b11,
+        };
+        assert_eq!(sim.get_u64(&port_q)?, unknown);
 
         sim.set(&port_c, [0]);
-        sim.set(&port_d, [1, 0]);
+        sim.set_u64(&port_d, 0b01);
         sim.simulate()?;
 
-        assert_eq!(sim.get(&port_q), [Logic::X; 2]);
+        assert_eq!(sim.get_u64(&port_q)?, unknown);
 
         sim.set(&port_c, [1]);
         sim.simulate()?;
 
-        assert_eq!(sim.get(&port_q), [Logic::_1, Logic::_0]);
+        assert_eq!(sim.get_u64(&port_q)?, IntValue::Known(0b01));
 
         sim.set(&port_c, [0]);
-        sim.set(&port_d, [0, 1]);
+        sim.set_u64(&port_d, 0b10);
         sim.simulate()?;
 
         sim.set(&port_c, [1]);
         sim.simulate()?;
 
-        assert_eq!(sim.get(&port_q), [Logic::_0, Logic::_1]);
+        assert_eq!(sim.get_u64(&port_q)?, IntValue::Known(0b10));
 
         Ok(())
     })()
@@ -105,19 +110,15 @@ pub fn test_add() {
         let port_a = module.get_in_port::<8>("a")?;
         let port_b = module.get_in_port::<8>("b")?;
         let port_y = module.get_out_port::<8>("y")?;
-        let mut sim: Sim<'_> = Sim::new(&module);
-
-        for int_a in 0..15 {
-            for int_b in 0..15 {
-                let a = Logic::to_bits::<8>(int_a);
-                let b = Logic::to_bits::<8>(int_b);
-                let expected = Logic::to_bits::<8>(int_a + int_b);
+        let mut sim: Sim<'_> = Sim::new(module);
 
-                sim.set(&port_a, a);
-                sim.set(&port_b, b);
+        for a in 0..15 {
+            for b in 0..15 {
+                sim.set_u64(&port_a, a);
+                sim.set_u64(&port_b, b);
                 sim.simulate()?;
 
-                assert_eq!(sim.get(&port_y), expected);
+                assert_eq!(sim.get_u64(&port_y)?, IntValue::Known(a + b));
             }
         }
 
@@ -125,3 +126,38 @@ pub fn test_add() {
     })()
     .unwrap()
 }
+
+#[test]
+pub fn test_shift() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<1>("d")?;
+        let port_q = module.get_out_port::<3>("q")?;
+        let mut sim: Sim<'_> = Sim::new(module);
+
+        for d in [1, 0, 1] {
+            sim.set(&port_c, [0]);
+            sim.set(&port_d, [d]);
+            sim.simulate()?;
+
+            sim.set(&port_c, [1]);
+            sim.simulate()?;
+        }
+
+        assert_eq!(sim.get_u64(&port_q)?, IntValue::Known(0b101));
+
+        sim.set(&port_c, [0]);
+        sim.set(&port_d, [0]);
+        sim.simulate()?;
+
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+
+        assert_eq!(sim.get_u64(&port_q)?, IntValue::Known(0b010));
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -63,3 +63,97 @@ module Add (
 );
   assign y = a + b;
 endmodule
+
+module Pipeline (
+    input  logic c,
+    input  logic[3:0] a,
+    input  logic[3:0] b,
+    output logic[3:0] y
+);
+  logic[3:0] q;
+  always_ff @(posedge c) begin
+    q <= a & b;
+  end
+  assign y = q | a;
+endmodule
+
+module Shift (
+    input  logic c,
+    input  logic d,
+    output logic[2:0] q
+);
+  always_ff @(posedge c) begin
+    q <= {q[1:0], d};
+  end
+endmodule
+
+module Blocks (
+    input  logic c,
+    input  logic[3:0] a,
+    input  logic[3:0] b,
+    output logic[3:0] x,
+    output logic[3:0] y
+);
+  always_ff @(posedge c) begin
+    x <= x + a;
+    y <= y | b;
+  end
+endmodule
+
+module Init (
+    input  logic c,
+    input  logic d,
+    output logic[1:0] q = 2'b01,
+    output logic r
+);
+  always_ff @(posedge c) begin
+    q <= {q[0], d};
+    r <= d;
+  end
+endmodule
+
+module TwoClocks (
+    input  logic c0,
+    input  logic c1,
+    input  logic d,
+    output logic q0,
+    output logic q1
+);
+  always_ff @(posedge c0) begin
+    q0 <= d;
+  end
+  always_ff @(posedge c1) begin
+    q1 <= q0;
+  end
+endmodule
+
+module Ports (
+    input  logic c,
+    input  logic signed[7:0] addr,
+    output logic signed[7:0] data,
+    output logic[1:0] flags
+);
+  assign data = addr;
+  assign flags = {c, c};
+endmodule
+
+module AndDeMorgan (
+    input  logic a,
+    input  logic b,
+    output logic y
+);
+  assign y = ~(~a | ~b);
+endmodule
+
+module Add12 (
+    input  logic[11:0] a,
+    input  logic[11:0] b,
+    output logic[11:0] y
+);
+  assign y = a + b;
+endmodule
+
+module Sink (
+    input  logic a
+);
+endmodule
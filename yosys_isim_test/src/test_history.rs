@@ -0,0 +1,118 @@
+use crate::TEST_GATES_SV;
+use crate::util::random::Stimulus;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::Random;
+use yosys_isim::common::SimError;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::History;
+use yosys_isim::sim::Scheduler;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::Snapshot;
+
+const HISTORY: History = History {
+    checkpoint_interval: 16,
+    max_frames: 1000,
+};
+
+#[test]
+pub fn test_goto_frame() {
+    for module in TEST_GATES_SV.deref().iter() {
+        for scheduler in [Scheduler::FullSweep, Scheduler::EventDriven] {
+            do_test_goto_frame(module, scheduler).unwrap();
+        }
+    }
+}
+
+/// every frame reconstructed from the history has to be the same as when it was simulated
+pub fn do_test_goto_frame(module: &Module, scheduler: Scheduler) -> Result<(), SimError> {
+    let mut sim = Sim::with_config(
+        module,
+        SimConfig {
+            scheduler,
+            history: Some(HISTORY),
+            ..Default::default()
+        },
+    );
+
+    let mut snapshots: Vec<Snapshot> = vec![sim.snapshot()];
+    let mut random = Random::new(0x0123_4567_89ab_cdef);
+    for _ in 0..100 {
+        stimulate(module, &mut sim, &mut random);
+        sim.simulate()?;
+        snapshots.push(sim.snapshot());
+    }
+    assert_eq!(sim.history_frames(), Some(0..=100));
+
+    for frame in [37, 0, 100, 16, 15, 99, 64] {
+        sim.goto_frame(frame)?;
+        assert_eq!(sim.frame(), frame);
+        assert_eq!(
+            sim.snapshot(),
+            snapshots[frame],
+            "{} in frame {}",
+            module.name,
+            frame
+        );
+    }
+
+    sim.step_back(10)?;
+    assert_eq!(sim.snapshot(), snapshots[54]);
+
+    // continuing with other stimulus forgets the frames after it
+    stimulate(module, &mut sim, &mut random);
+    sim.simulate()?;
+    assert_eq!(sim.history_frames(), Some(0..=55));
+
+    Ok(())
+}
+
+#[test]
+pub fn test_bounded_history() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Pipeline")?;
+        let mut sim = Sim::with_config(
+            module,
+            SimConfig {
+                engine: Engine::Tape,
+                history: Some(History {
+                    checkpoint_interval: 8,
+                    max_frames: 20,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let mut random = Random::new(0x0123_4567_89ab_cdef);
+        let mut snapshots: Vec<Snapshot> = vec![sim.snapshot()];
+        for _ in 0..100 {
+            stimulate(module, &mut sim, &mut random);
+            sim.simulate()?;
+            snapshots.push(sim.snapshot());
+        }
+
+        // the last checkpoint at least 20 frames ago is kept
+        assert_eq!(sim.history_frames(), Some(72..=100));
+
+        sim.goto_frame(75)?;
+        assert_eq!(sim.snapshot(), snapshots[75]);
+
+        let err = sim.goto_frame(71).unwrap_err();
+        assert_eq!(err.code(), "FRAME_NOT_IN_HISTORY");
+
+        let err = Sim::new(module).step_back(1).unwrap_err();
+        assert_eq!(err.code(), "HISTORY_DISABLED");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+fn stimulate(module: &Module, sim: &mut Sim, random: &mut Random) {
+    for port in module.in_ports.iter() {
+        let logics = random.bits(port.wires.len());
+        sim.set_dynamic(port, &logics);
+    }
+}
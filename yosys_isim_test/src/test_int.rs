@@ -0,0 +1,123 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::IntValue;
+use yosys_isim::sim::Sim;
+
+#[test]
+pub fn test_signed() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+
+        let port_a = module.get_in_port::<8>("a")?;
+        let port_b = module.get_in_port::<8>("b")?;
+        let port_y = module.get_out_port::<8>("y")?;
+        let mut sim = Sim::new(module);
+
+        for (a, b) in [(-3, 1), (-128, 127), (100, 27), (100, 28), (-1, -1)] {
+            sim.set_i64(&port_a, a);
+            sim.set_i64(&port_b, b);
+            sim.simulate()?;
+
+            let y = (a + b) as i8;
+            assert_eq!(sim.get_i64(&port_y)?, IntValue::Known(y as i64));
+            assert_eq!(sim.get_u64(&port_y)?, IntValue::Known(y as u8 as u64));
+        }
+
+        // only the lower 8 bits are set
+        sim.set_u64(&port_a, 0x1ff);
+        sim.set_u64(&port_b, 0);
+        sim.simulate()?;
+        assert_eq!(sim.get_u64(&port_y)?, IntValue::Known(0xff));
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_big() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Or32")?;
+
+        let port_a = module.get_in_port::<32>("a")?;
+        let port_b = module.get_in_port::<32>("b")?;
+        let port_y = module.get_out_port::<32>("y")?;
+        let mut sim = Sim::new(module);
+
+        sim.set_big(&port_a, &[0xffff_0000_0000_1234, 1]);
+        sim.set_big_signed(&port_b, &[]);
+        sim.simulate()?;
+        assert_eq!(sim.get_big(&port_y), IntValue::Known(vec![0x1234]));
+
+        sim.set_big_signed(&port_b, &[u64::MAX << 31]);
+        sim.simulate()?;
+        assert_eq!(sim.get_big(&port_y), IntValue::Known(vec![0x8000_1234]));
+        assert_eq!(
+            sim.get_i64(&port_y)?,
+            IntValue::Known(0x8000_1234u32 as i32 as i64)
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_unknown() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Pipeline")?;
+
+        let port_a = module.get_in_port::<4>("a")?;
+        let port_y = module.get_out_port::<4>("y")?;
+        let mut sim = Sim::new(module);
+
+        // y = q | a, with q not yet clocked
+        sim.set_u64(&port_a, 0b0110);
+        sim.simulate()?;
+
+        let y = sim.get_u64(&port_y)?;
+        assert!(!y.is_known());
+        assert_eq!(
+            y,
+            IntValue::Unknown {
+                value: 0b0110,
+                x_mask: 0b1001
+            }
+        );
+        assert_eq!(y.known(), None);
+
+        // the X of the sign bit is extended as well
+        assert_eq!(
+            sim.get_i64(&port_y)?,
+            IntValue::Unknown {
+                value: 0b0110,
+                x_mask: !0b0110
+            }
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_port_too_wide() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+        let port_y = module.get_out_port::<8>("y")?;
+        let mut port_y_wide = port_y.clone();
+        port_y_wide
+            .wires
+            .extend(std::iter::repeat_n(port_y.wires[0], 60));
+        let mut sim = Sim::new(module);
+
+        let err = sim.get_u64(&port_y_wide).unwrap_err();
+        assert_eq!(err.code(), "PORT_TOO_WIDE");
+        assert_eq!(sim.get_big(&port_y_wide).value().len(), 2);
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,21 @@
+use crate::TEST_GATES_SV;
+use crate::util::differential::assert_same_outputs;
+use std::ops::Deref;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_jit_matches_interpreter() {
+    for module in TEST_GATES_SV.deref().iter() {
+        assert_same_outputs(
+            module,
+            SimConfig::default(),
+            SimConfig {
+                engine: Engine::Jit,
+                ..Default::default()
+            },
+            0xd1b5_4a32_d192_ed03,
+        )
+        .unwrap();
+    }
+}
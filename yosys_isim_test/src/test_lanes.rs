@@ -0,0 +1,89 @@
+use crate::TEST_GATES_SV;
+use crate::util::random::Stimulus;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::Random;
+use yosys_isim::common::SimError;
+use yosys_isim::model::Module;
+use yosys_isim::sim::LaneSim;
+use yosys_isim::sim::Lanes;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::NUM_LANES;
+use yosys_isim::sim::Sim;
+
+#[test]
+pub fn test_lanes_match_sim() {
+    for module in TEST_GATES_SV.deref().iter() {
+        do_test_lanes(module).unwrap();
+    }
+}
+
+pub fn do_test_lanes(module: &Module) -> Result<(), SimError> {
+    let mut lane_sim = LaneSim::new(module);
+    let mut sims: Vec<Sim> = (0..NUM_LANES).map(|_| Sim::new(module)).collect();
+
+    let mut random = Random::new(0x9e37_79b9_7f4a_7c15);
+    for frame in 0..32 {
+        for port in module.in_ports.iter() {
+            for (lane, sim) in sims.iter_mut().enumerate() {
+                let logics = random.logics_with_x(port.wires.len());
+
+                sim.set_dynamic(port, &logics);
+                lane_sim.set_lane_dynamic(port, lane, &logics);
+            }
+        }
+
+        lane_sim.simulate()?;
+        for sim in sims.iter_mut() {
+            sim.simulate()?;
+        }
+
+        for port in module.out_ports.iter() {
+            for (lane, sim) in sims.iter_mut().enumerate() {
+                assert_eq!(
+                    lane_sim.get_lane_dynamic(port, lane),
+                    sim.get_dynamic(port),
+                    "{}.{} differs in frame {} on lane {}",
+                    module.name,
+                    port.name,
+                    frame,
+                    lane
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn test_lanes_normalized() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Or")?;
+        let port_a = module.get_in_port::<1>("a")?;
+        let port_b = module.in_ports.iter().find(|it| it.name == "b").unwrap();
+        let port_y = module.get_out_port::<1>("y")?;
+
+        // lane 0 has both bit planes set, the `1` is dropped
+        let mut lane_sim = LaneSim::new(module);
+        lane_sim.set(
+            &port_a,
+            [Lanes {
+                value: 1,
+                unknown: 1,
+            }],
+        );
+        lane_sim.set_dynamic(port_b, &[Lanes::splat(Logic::_0)]);
+        lane_sim.simulate()?;
+        assert_eq!(
+            lane_sim.get(&port_y),
+            [Lanes {
+                value: 0,
+                unknown: 1
+            }]
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,85 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::IntValue;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::Radix;
+use yosys_isim::sim::Sim;
+
+#[test]
+pub fn test_parse_literals() {
+    (|| -> Result<(), SimError> {
+        let value: LogicVec = "8'b10x1_0zz1".parse()?;
+        assert_eq!(
+            *value,
+            [1, 2, 2, 0, 1, 2, 0, 1].map(|it| match it {
+                2 => Logic::X,
+                it => Logic::from(it),
+            })
+        );
+        assert_eq!(value.to_string(), "8'b10x10xx1");
+        assert_eq!(format!("{:x}", value), "8'hXX");
+
+        let value: LogicVec = "16'hDEAD".parse()?;
+        assert_eq!(value.to_int(), IntValue::Known(vec![0xdead]));
+        assert_eq!(format!("{:x}", value), "16'hdead");
+        assert_eq!(format!("{:X}", value), "16'hDEAD");
+
+        let value: LogicVec = "4'd9".parse()?;
+        assert_eq!(value, LogicVec::from_u64(4, 9));
+        assert_eq!(format!("{:o}", value), "4'o11");
+        assert_eq!(value.to_literal(Radix::Dec), "4'd9");
+
+        // unsized literals have 32 bits
+        assert_eq!("'hff".parse::<LogicVec>()?, LogicVec::from_u64(32, 0xff));
+        assert_eq!("42".parse::<LogicVec>()?, LogicVec::from_u64(32, 42));
+        assert_eq!("'sd7".parse::<LogicVec>()?, LogicVec::from_u64(32, 7));
+
+        // truncated, or extended with X if the leftmost digit is X
+        assert_eq!("3'b1111".parse::<LogicVec>()?, LogicVec::from_u64(3, 7));
+        assert_eq!("8'hx".parse::<LogicVec>()?, LogicVec(vec![Logic::X; 8]));
+        assert_eq!(
+            "6'b1x0000".parse::<LogicVec>()?.to_literal(Radix::Hex),
+            "6'hX0"
+        );
+        assert_eq!("4'dz".parse::<LogicVec>()?.to_literal(Radix::Dec), "4'dx");
+
+        let max = "128'd340282366920938463463374607431768211455";
+        let value: LogicVec = max.parse()?;
+        assert_eq!(value, LogicVec(vec![Logic::_1; 128]));
+        assert_eq!(value.to_literal(Radix::Dec), max);
+
+        for literal in ["8'q1", "8'b102", "0'b1", "8'h", "1x"] {
+            let err = literal.parse::<LogicVec>().unwrap_err();
+            assert_eq!(err.code(), "ILLEGAL_LITERAL", "{}", literal);
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_set_get_vec() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+
+        let port_a = module.get_in_port::<8>("a")?;
+        let port_b = module.get_in_port::<8>("b")?;
+        let port_y = module.get_out_port::<8>("y")?;
+        let mut sim = Sim::new(module);
+
+        sim.set_vec(&port_a, &"8'h3f".parse()?)?;
+        sim.set_vec(&port_b, &"8'd1".parse()?)?;
+        sim.simulate()?;
+        assert_eq!(format!("{:x}", sim.get_vec(&port_y)), "8'h40");
+
+        let err = sim.set_vec(&port_a, &"4'h3".parse()?).unwrap_err();
+        assert_eq!(err.code(), "PORT_WIDTH_MISMATCH");
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,67 @@
+use crate::TEST_GATES_SV;
+use crate::util::differential::assert_same_outputs;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Parallel;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+const PARALLEL: Parallel = Parallel {
+    num_threads: 4,
+    min_cells: 0,
+};
+
+#[test]
+pub fn test_partitions() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Blocks")?;
+
+        let sim = Sim::with_config(
+            module,
+            SimConfig {
+                parallel: Some(PARALLEL),
+                ..Default::default()
+            },
+        );
+        assert_eq!(sim.num_partitions(), 2);
+
+        let sim = Sim::with_config(
+            module,
+            SimConfig {
+                parallel: Some(Parallel {
+                    min_cells: module.cells.len() + 1,
+                    ..PARALLEL
+                }),
+                ..Default::default()
+            },
+        );
+        assert_eq!(sim.num_partitions(), 1);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_parallel_matches_serial() {
+    for module in TEST_GATES_SV.deref().iter() {
+        for engine in [Engine::Interpreter, Engine::Tape] {
+            assert_same_outputs(
+                module,
+                SimConfig {
+                    engine,
+                    ..Default::default()
+                },
+                SimConfig {
+                    engine,
+                    parallel: Some(PARALLEL),
+                    ..Default::default()
+                },
+                0x0123_4567_89ab_cdef,
+            )
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::model::PortDirection;
+use yosys_isim::model::PortInfo;
+use yosys_isim::sim::IntValue;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::Sim;
+
+#[test]
+pub fn test_port_infos() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Ports")?;
+
+        let port_info = |name: &str, direction, width, signed| PortInfo {
+            name: name.to_string(),
+            direction,
+            width,
+            signed,
+        };
+        assert_eq!(
+            module.ports,
+            [
+                port_info("addr", PortDirection::Input, 8, true),
+                port_info("c", PortDirection::Input, 1, false),
+                port_info("data", PortDirection::Output, 8, true),
+                port_info("flags", PortDirection::Output, 2, false),
+            ]
+        );
+        assert_eq!(module.port_info("flags")?.width, 2);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_port_handles() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Ports")?;
+        let mut sim = Sim::new(module);
+
+        let port_addr = sim.port("addr")?;
+        let port_c = sim.port("c")?;
+        let port_data = sim.port("data")?;
+        let port_flags = sim.port("flags")?;
+        assert_eq!(port_addr.width(), 8);
+        assert_eq!(port_data.direction(), PortDirection::Output);
+
+        sim.set_port(&port_addr, &"8'hc3".parse::<LogicVec>()?)?;
+        sim.set_port(&port_c, &[1])?;
+        sim.simulate()?;
+
+        assert_eq!(format!("{:x}", sim.get_port(&port_data)), "8'hc3");
+        assert_eq!(sim.get_port(&port_flags).to_string(), "2'b11");
+        // in ports can be read as well
+        assert_eq!(sim.get_port(&port_c).to_string(), "1'b1");
+
+        // the typed accessors take handles too
+        let port_data = port_data.to_out_port()?;
+        assert_eq!(
+            sim.get_i64(&port_data)?,
+            IntValue::Known(0xc3u8 as i8 as i64)
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_port_handle_errors() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Ports")?;
+        let mut sim = Sim::new(module);
+
+        let err = sim.port("nope").unwrap_err();
+        assert_eq!(err.code(), "PORT_NOT_FOUND");
+
+        let port_addr = sim.port("addr")?;
+        let err = sim.set_port(&port_addr, &[1, 0]).unwrap_err();
+        assert_eq!(err.code(), "PORT_WIDTH_MISMATCH");
+
+        let port_data = sim.port("data")?;
+        let err = sim.set_port(&port_data, &[0; 8]).unwrap_err();
+        assert_eq!(err.code(), "WRONG_PORT_DIRECTION");
+        assert_eq!(
+            err.to_string(),
+            "port [data] of module [Ports] is an output"
+        );
+        let err = port_addr.to_out_port().unwrap_err();
+        assert_eq!(err.code(), "WRONG_PORT_DIRECTION");
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,121 @@
+use crate::TEST_GATES_SV;
+use std::fs;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::IntValue;
+use yosys_isim::sim::LogicVec;
+use yosys_isim::sim::Sim;
+use yosys_isim::verify::PortConstraint;
+use yosys_isim::verify::PropertyTest;
+
+pub fn get(sim: &mut Sim, port: &str) -> Result<IntValue<u64>, SimError> {
+    let port = sim.port(port)?;
+    Ok(sim.get_port(&port).to_int().map(|it| it[0]))
+}
+
+#[test]
+pub fn test_property_passes() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+        let mut test = PropertyTest::new(module);
+        test.constrain("a", PortConstraint::Range { min: 10, max: 100 })?;
+        test.constrain("b", PortConstraint::OneHot)?;
+        test.num_runs = 10;
+
+        let report = test.check(|sim| {
+            let a = get(sim, "a")?.unwrap();
+            let b = get(sim, "b")?.unwrap();
+            let y = get(sim, "y")?.unwrap();
+            Ok((10..=100).contains(&a) && b.count_ones() == 1 && y == (a + b) & 0xff)
+        })?;
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.to_string(), "10 runs passed\n");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_property_hold() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("And32")?;
+        let mut test = PropertyTest::new(module);
+        test.hold("a", 4)?;
+        test.num_runs = 3;
+        test.num_frames = 40;
+
+        let mut values: Vec<LogicVec> = Vec::new();
+        let report = test.check(|sim| {
+            values.push(sim.get_port(&sim.port("a")?));
+            Ok(true)
+        })?;
+        assert!(report.passed());
+        for block in values.chunks(4) {
+            assert!(block.iter().all(|it| *it == block[0]));
+        }
+        assert_ne!(values[0], values[4]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_property_shrinks() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+        let dump = std::env::temp_dir().join("yosys_isim_test_property_shrinks.csv");
+        let mut test = PropertyTest::new(module);
+        test.constrain("c", PortConstraint::Toggle)?;
+        test.dump = Some(dump.to_str().unwrap().to_string());
+
+        // fails once three ones are shifted in
+        let mut invariant =
+            |sim: &mut Sim| -> Result<bool, SimError> { Ok(get(sim, "q")?.known() != Some(0b111)) };
+        let report = test.check(&mut invariant)?;
+        let failure = report.failure.as_ref().unwrap();
+        assert!(failure.num_frames >= failure.sequence.len());
+        assert_eq!(
+            failure.table(),
+            "c, d\n\
+             1'h0, 1'h0\n\
+             1'h1, 1'h1\n\
+             1'h0, 1'h0\n\
+             1'h1, 1'h1\n\
+             1'h0, 1'h0\n\
+             1'h1, 1'h1\n"
+        );
+        assert_eq!(fs::read_to_string(&dump)?, failure.table());
+
+        // replaying the reproducer fails in its last frame
+        let mut sim = Sim::new(module);
+        let mut passed = Vec::new();
+        failure.to_test_vectors().run_with(&mut sim, |sim| {
+            passed.push(invariant(sim)?);
+            Ok(())
+        })?;
+        assert_eq!(passed, [true, true, true, true, true, false]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_property_errors() {
+    let module = TEST_GATES_SV.deref().iter().find_by_name("Add").unwrap();
+    let mut test = PropertyTest::new(module);
+
+    let err = test
+        .constrain("a", PortConstraint::Range { min: 0, max: 256 })
+        .unwrap_err();
+    assert_eq!(err.code(), "ILLEGAL_CONSTRAINT");
+
+    let err = test.constrain("y", PortConstraint::OneHot).unwrap_err();
+    assert_eq!(err.code(), "WRONG_PORT_DIRECTION");
+
+    let err = test.hold("nope", 2).unwrap_err();
+    assert_eq!(err.code(), "PORT_NOT_FOUND");
+}
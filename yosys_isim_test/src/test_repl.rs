@@ -0,0 +1,133 @@
+use crate::TEST_GATES_NETLIST;
+use crate::TEST_GATES_SV;
+use yosys_isim::sim::Radix;
+use yosys_isim_cli::CliError;
+use yosys_isim_cli::Repl;
+use yosys_isim_cli::Reply;
+
+pub fn execute(repl: &mut Repl, line: &str) -> Result<String, CliError> {
+    match repl.execute(line)? {
+        Reply::Output(output) => Ok(output),
+        Reply::Load(modules) => Ok(format!("load {}", modules.len())),
+        Reply::Quit => Ok("quit".to_string()),
+    }
+}
+
+pub fn load_top(top: &str) -> Result<Repl<'static>, CliError> {
+    let mut repl = Repl::new(&TEST_GATES_SV, Radix::Hex);
+    execute(&mut repl, &format!("top {}", top))?;
+    Ok(repl)
+}
+
+#[test]
+pub fn test_repl_step() {
+    (|| -> Result<(), CliError> {
+        let mut repl = load_top("Shift")?;
+
+        assert_eq!(execute(&mut repl, "clock c 10")?, "");
+        assert_eq!(execute(&mut repl, "set d 1")?, "");
+        assert_eq!(execute(&mut repl, "step 4")?, "frame 4, time 20");
+        assert_eq!(execute(&mut repl, "radix b")?, "");
+        assert_eq!(execute(&mut repl, "get q d")?, "q = 3'bx11\nd = 1'b1");
+
+        assert_eq!(execute(&mut repl, "back 2")?, "frame 2, time 10");
+        assert_eq!(execute(&mut repl, "get q")?, "q = 3'bxx1");
+
+        assert_eq!(execute(&mut repl, "watch q 3'b111")?, "watchpoint 0");
+        assert_eq!(execute(&mut repl, "watches")?, "0: q == 3'b111");
+        assert_eq!(
+            execute(&mut repl, "step 100")?,
+            "frame 6, time 30\nwatchpoint 0 (q == 3'b111): q = 3'b111"
+        );
+        assert_eq!(execute(&mut repl, "quit")?, "quit");
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_repl_ports_and_nets() {
+    (|| -> Result<(), CliError> {
+        let mut repl = load_top("Ports")?;
+
+        assert_eq!(
+            execute(&mut repl, "ports")?,
+            "input  addr [8] signed\n\
+             input  c [1]\n\
+             output data [8] signed\n\
+             output flags [2]"
+        );
+        assert_eq!(execute(&mut repl, "nets ?dd*")?, "addr");
+        assert_eq!(execute(&mut repl, "nets *a")?, "data");
+
+        execute(&mut repl, "set addr 8'h3f")?;
+        execute(&mut repl, "step")?;
+        assert_eq!(execute(&mut repl, "get data")?, "data = 8'h3f");
+
+        assert_eq!(
+            repl.completions("get fl", 6),
+            (4, vec!["flags".to_string()])
+        );
+        assert_eq!(
+            repl.completions("wa", 2),
+            (0, vec!["watch".to_string(), "watches".to_string()])
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_repl_reload() {
+    (|| -> Result<(), CliError> {
+        let mut repl = load_top("Shift")?;
+        assert_eq!(execute(&mut repl, "set d 1")?, "");
+        assert_eq!(execute(&mut repl, "radix b")?, "");
+
+        // the caller owns the loaded modules and builds a new repl on them, which has no top yet
+        let Reply::Load(modules) =
+            repl.execute(&format!("load {}", TEST_GATES_NETLIST.as_str()))?
+        else {
+            panic!("expected the modules of the netlist");
+        };
+        let mut repl = Repl::new(&modules, repl.radix());
+        assert_eq!(
+            repl.loaded()?,
+            format!("loaded {} modules", TEST_GATES_SV.len())
+        );
+        let err = execute(&mut repl, "get q").unwrap_err();
+        assert_eq!(err.to_string(), "no top module, try `load` and `top`");
+
+        assert_eq!(execute(&mut repl, "top Shift")?, "top Shift");
+        assert_eq!(execute(&mut repl, "get d")?, "d = 1'bx");
+
+        let err = execute(&mut repl, "load missing.json").unwrap_err();
+        assert!(matches!(err, CliError::Sim(_)));
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_repl_errors() {
+    let mut repl = Repl::new(&[], Radix::Hex);
+
+    let err = execute(&mut repl, "get y").unwrap_err();
+    assert_eq!(err.to_string(), "no top module, try `load` and `top`");
+
+    let err = execute(&mut repl, "fly").unwrap_err();
+    assert_eq!(err.to_string(), "unknown command [fly], try `help`");
+
+    let err = execute(&mut repl, "set a").unwrap_err();
+    assert_eq!(err.to_string(), "usage: set <port> <literal>");
+
+    let mut repl = load_top("Shift").unwrap();
+    let err = execute(&mut repl, "set q 1").unwrap_err();
+    assert!(matches!(err, CliError::Sim(_)));
+
+    let err = execute(&mut repl, "set d 2").unwrap_err();
+    assert_eq!(err.to_string(), "[2] does not fit into [1] bits");
+}
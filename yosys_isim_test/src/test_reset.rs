@@ -0,0 +1,132 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::RegisterInit;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_init_attribute() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Init")?;
+
+        let port_q = module.get_out_port::<2>("q")?;
+        let port_r = module.get_out_port::<1>("r")?;
+
+        let net_q = module.nets.iter().find_by_name("q")?;
+        assert_eq!(
+            net_q.init.as_deref(),
+            Some([Logic::_1, Logic::_0].as_slice())
+        );
+
+        let mut sim = Sim::new(module);
+        assert_eq!(sim.get(&port_q), [Logic::_1, Logic::_0]);
+        assert_eq!(sim.get(&port_r), [Logic::X]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_register_init() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Init")?;
+
+        let port_q = module.get_out_port::<2>("q")?;
+        let port_r = module.get_out_port::<1>("r")?;
+
+        let new_sim = |register_init| {
+            Sim::with_config(
+                module,
+                SimConfig {
+                    register_init,
+                    ..Default::default()
+                },
+            )
+        };
+
+        // the init attribute wins
+        let mut sim = new_sim(RegisterInit::Zero);
+        assert_eq!(sim.get(&port_q), [Logic::_1, Logic::_0]);
+        assert_eq!(sim.get(&port_r), [Logic::_0]);
+
+        let mut sim = new_sim(RegisterInit::One);
+        assert_eq!(sim.get(&port_r), [Logic::_1]);
+
+        let r = new_sim(RegisterInit::Random { seed: 42 }).get(&port_r);
+        assert_ne!(r, [Logic::X]);
+        for _ in 0..4 {
+            assert_eq!(new_sim(RegisterInit::Random { seed: 42 }).get(&port_r), r);
+        }
+
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Blocks")?;
+        let port_x = module.get_out_port::<4>("x")?;
+        let seeds: Vec<[Logic; 4]> = (0..8)
+            .map(|seed| {
+                Sim::with_config(
+                    module,
+                    SimConfig {
+                        register_init: RegisterInit::Random { seed },
+                        ..Default::default()
+                    },
+                )
+                .get(&port_x)
+            })
+            .collect();
+        assert!(seeds.iter().any(|it| *it != seeds[0]));
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_reset() {
+    for engine in [Engine::Interpreter, Engine::Tape, Engine::Jit] {
+        do_test_reset(engine).unwrap();
+    }
+}
+
+pub fn do_test_reset(engine: Engine) -> Result<(), SimError> {
+    let module = TEST_GATES_SV.deref().iter().find_by_name("Init")?;
+
+    let port_c = module.get_in_port::<1>("c")?;
+    let port_d = module.get_in_port::<1>("d")?;
+    let port_q = module.get_out_port::<2>("q")?;
+    let port_r = module.get_out_port::<1>("r")?;
+    let mut sim = Sim::with_config(
+        module,
+        SimConfig {
+            engine,
+            ..Default::default()
+        },
+    );
+
+    for _ in 0..2 {
+        sim.set(&port_c, [0]);
+        sim.set(&port_d, [0]);
+        sim.simulate()?;
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+    }
+    assert_eq!(sim.get(&port_q), [Logic::_0, Logic::_0]);
+    assert_eq!(sim.get(&port_r), [Logic::_0]);
+
+    sim.reset();
+    assert_eq!(sim.frame(), 0);
+    assert_eq!(sim.get(&port_q), [Logic::_1, Logic::_0]);
+    assert_eq!(sim.get(&port_r), [Logic::X]);
+
+    sim.set(&port_c, [0]);
+    sim.set(&port_d, [0]);
+    sim.simulate()?;
+    sim.set(&port_c, [1]);
+    sim.simulate()?;
+    assert_eq!(sim.get(&port_q), [Logic::_0, Logic::_1]);
+
+    Ok(())
+}
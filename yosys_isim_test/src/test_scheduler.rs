@@ -0,0 +1,24 @@
+use crate::TEST_GATES_SV;
+use crate::util::differential::assert_same_outputs;
+use std::ops::Deref;
+use yosys_isim::sim::Scheduler;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_event_driven_matches_full_sweep() {
+    for module in TEST_GATES_SV.deref().iter() {
+        assert_same_outputs(
+            module,
+            SimConfig {
+                scheduler: Scheduler::FullSweep,
+                ..Default::default()
+            },
+            SimConfig {
+                scheduler: Scheduler::EventDriven,
+                ..Default::default()
+            },
+            0x2545_f491_4f6c_dd1d,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use crate::TEST_GATES_SV;
+use crate::util::random::Stimulus;
+use std::fs;
+use std::ops::Deref;
+use std::sync::Arc;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::Random;
+use yosys_isim::common::SimError;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Design;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::Parallel;
+use yosys_isim::sim::Scheduler;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::Snapshot;
+
+#[test]
+pub fn test_restore() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<1>("d")?;
+        let port_q = module.get_out_port::<3>("q")?;
+        let mut sim = Sim::new(module);
+
+        let shift = |sim: &mut Sim, d: u8| -> Result<[Logic; 3], SimError> {
+            sim.set(&port_c, [0]);
+            sim.set(&port_d, [d]);
+            sim.simulate()?;
+            sim.set(&port_c, [1]);
+            sim.simulate()?;
+            Ok(sim.get(&port_q))
+        };
+
+        shift(&mut sim, 1)?;
+        shift(&mut sim, 0)?;
+        let snapshot = sim.snapshot();
+        assert_eq!(snapshot.frame, 4);
+
+        assert_eq!(shift(&mut sim, 1)?, [Logic::_1, Logic::_0, Logic::_1]);
+        assert_eq!(shift(&mut sim, 1)?, [Logic::_1, Logic::_1, Logic::_0]);
+
+        sim.restore(&snapshot)?;
+        assert_eq!(sim.frame(), 4);
+        assert_eq!(shift(&mut sim, 0)?, [Logic::_0, Logic::_0, Logic::_1]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_restore_matches_uninterrupted() {
+    let configs = [
+        SimConfig::default(),
+        SimConfig {
+            scheduler: Scheduler::EventDriven,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Jit,
+            ..Default::default()
+        },
+        SimConfig {
+            engine: Engine::Tape,
+            parallel: Some(Parallel {
+                num_threads: 2,
+                min_cells: 0,
+            }),
+            ..Default::default()
+        },
+    ];
+
+    for module in TEST_GATES_SV.deref().iter() {
+        for config in configs.iter() {
+            do_test_restore(module, config.clone()).unwrap();
+        }
+    }
+}
+
+/// takes a snapshot halfway through and restores it into a fresh [Sim] spawned from the
+/// same [Design], after which both have to behave the same
+pub fn do_test_restore(module: &Module, config: SimConfig) -> Result<(), SimError> {
+    let design = Arc::new(Design::with_config(module, config));
+    let mut sim = design.spawn();
+
+    let mut random = Random::new(0x0123_4567_89ab_cdef);
+    let mut stimulate = |sim: &mut Sim| {
+        for port in module.in_ports.iter() {
+            let logics = random.bits(port.wires.len());
+            sim.set_dynamic(port, &logics);
+        }
+    };
+
+    for _ in 0..32 {
+        stimulate(&mut sim);
+        sim.simulate()?;
+    }
+    // stimulus that is set but not yet simulated belongs to the snapshot
+    stimulate(&mut sim);
+
+    let mut restored = design.spawn();
+    restored.restore(&sim.snapshot())?;
+
+    for frame in 32..64 {
+        sim.simulate()?;
+        restored.simulate()?;
+
+        for port in module.out_ports.iter() {
+            assert_eq!(
+                restored.get_dynamic(port),
+                sim.get_dynamic(port),
+                "{}.{} differs in frame {}",
+                module.name,
+                port.name,
+                frame
+            );
+        }
+
+        stimulate(&mut sim);
+        let snapshot = sim.snapshot();
+        restored.restore(&snapshot)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn test_snapshot_file() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Dff")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<2>("d")?;
+        let port_q = module.get_out_port::<2>("q")?;
+        let mut sim = Sim::new(module);
+
+        sim.set(&port_c, [0]);
+        sim.set(&port_d, [1, 0]);
+        sim.simulate()?;
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+
+        fs::create_dir_all("target")?;
+        sim.snapshot()
+            .write_to_file("target/test_snapshot_file.json")?;
+        let snapshot = Snapshot::read_from_file("target/test_snapshot_file.json")?;
+        assert_eq!(snapshot, sim.snapshot());
+
+        let mut restored = Sim::with_config(
+            module,
+            SimConfig {
+                engine: Engine::Tape,
+                ..Default::default()
+            },
+        );
+        restored.restore(&snapshot)?;
+        assert_eq!(restored.get(&port_q), [Logic::_1, Logic::_0]);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_snapshot_mismatch() {
+    (|| -> Result<(), SimError> {
+        let and = TEST_GATES_SV.deref().iter().find_by_name("And")?;
+        let dff = TEST_GATES_SV.deref().iter().find_by_name("Dff")?;
+
+        let snapshot = Sim::new(and).snapshot();
+        let err = Sim::new(dff).restore(&snapshot).unwrap_err();
+        assert_eq!(err.code(), "SNAPSHOT_MISMATCH");
+
+        Ok(())
+    })()
+    .unwrap()
+}
@@ -0,0 +1,21 @@
+use crate::TEST_GATES_SV;
+use crate::util::differential::assert_same_outputs;
+use std::ops::Deref;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::SimConfig;
+
+#[test]
+pub fn test_tape_matches_interpreter() {
+    for module in TEST_GATES_SV.deref().iter() {
+        assert_same_outputs(
+            module,
+            SimConfig::default(),
+            SimConfig {
+                engine: Engine::Tape,
+                ..Default::default()
+            },
+            0x9e37_79b9_7f4a_7c15,
+        )
+        .unwrap();
+    }
+}
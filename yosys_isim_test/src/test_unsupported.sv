@@ -0,0 +1,25 @@
+module Xor (
+    input  logic[3:0] a,
+    input  logic[3:0] b,
+    output logic[3:0] y
+);
+  assign y = a ^ b;
+endmodule
+
+module Xor3 (
+    input  logic a,
+    input  logic b,
+    input  logic c,
+    output logic y
+);
+  assign y = a ^ b ^ c;
+endmodule
+
+module Mux (
+    input  logic s,
+    input  logic[1:0] a,
+    input  logic[1:0] b,
+    output logic[1:0] y
+);
+  assign y = s ? a : b;
+endmodule
@@ -0,0 +1,96 @@
+use crate::TEST_GATES_SV;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Sim;
+use yosys_isim::verify::TestVectors;
+use yosys_isim::verify::VectorMismatch;
+
+#[test]
+pub fn test_vectors_from_file() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Dff")?;
+        let mut sim = Sim::new(module);
+
+        let vectors = TestVectors::read_from_file("src/test_vectors.csv")?;
+        let report = vectors.run(&mut sim)?;
+
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.num_rows, 6);
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_vectors_mismatches() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+        let mut sim = Sim::new(module);
+
+        let vectors = TestVectors::parse(
+            "a, b, y
+             1, 2, 3
+             8'hff, 1, 0
+             7, 7, 8'h0f
+             7, 7, 8'b0000_111x",
+        )?;
+        let report = vectors.run(&mut sim)?;
+
+        assert_eq!(
+            report.mismatches,
+            [VectorMismatch {
+                line: 4,
+                frame: 3,
+                port: "y".to_string(),
+                expected: "8'h0f".parse()?,
+                actual: "8'h0e".parse()?,
+            }]
+        );
+        assert_eq!(
+            report.to_string(),
+            "3 of 4 rows passed\n  line 4, frame 3: y = 8'b00001110, expected 8'b00001111\n"
+        );
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_vectors_errors() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Add")?;
+        let mut sim = Sim::new(module);
+
+        let err = TestVectors::parse("a, b, y\n1, 2").unwrap_err();
+        assert_eq!(err.code(), "ILLEGAL_TEST_VECTORS");
+
+        let err = TestVectors::parse("a, b, z\n1, 2, 3")?
+            .run(&mut sim)
+            .unwrap_err();
+        assert_eq!(err.code(), "PORT_NOT_FOUND");
+
+        let err = TestVectors::parse("a, b, y\n4'h1, 2, 3")?
+            .run(&mut sim)
+            .unwrap_err();
+        assert_eq!(err.code(), "ILLEGAL_TEST_VECTORS");
+
+        // an unsized literal is not truncated silently
+        let err = TestVectors::parse("a, b, y\n300, 2, 3")?
+            .run(&mut sim)
+            .unwrap_err();
+        assert_eq!(err.code(), "ILLEGAL_TEST_VECTORS");
+
+        // nothing was simulated
+        assert_eq!(sim.frame(), 0);
+
+        // the dropped bits of an unsized literal may be `0` or `X`
+        let report = TestVectors::parse("a, b, y\n255, 'hx, 'hx")?.run(&mut sim)?;
+        assert!(report.passed());
+
+        Ok(())
+    })()
+    .unwrap()
+}
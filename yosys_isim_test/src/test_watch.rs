@@ -0,0 +1,131 @@
+use crate::TEST_GATES_SV;
+use crate::test_force::configs;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Clock;
+use yosys_isim::sim::Halt;
+use yosys_isim::sim::HaltReason;
+use yosys_isim::sim::History;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::Watchpoint;
+
+#[test]
+pub fn test_run_until() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<1>("d")?;
+        let port_q = module.get_out_port::<3>("q")?;
+
+        for config in configs() {
+            let mut sim = Sim::with_config(module, config);
+            sim.add_clock(&port_c, Clock::new(10))?;
+            sim.set(&port_d, [1]);
+
+            // rising edges at 10, 20 and 30, one frame per edge
+            let halt = sim.run_until(|sim| sim.get(&port_q) == [Logic::_1; 3], 100)?;
+            assert_eq!(
+                halt,
+                Halt {
+                    frame: 6,
+                    reason: HaltReason::Predicate
+                }
+            );
+            assert_eq!(sim.time(), 30);
+
+            let halt = sim.run_until(|_| false, 3)?;
+            assert_eq!(
+                halt,
+                Halt {
+                    frame: 9,
+                    reason: HaltReason::MaxFrames
+                }
+            );
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_watchpoints() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+
+        let port_c = module.get_in_port::<1>("c")?;
+        let port_d = module.get_in_port::<1>("d")?;
+
+        for config in configs() {
+            let mut sim = Sim::with_config(
+                module,
+                SimConfig {
+                    history: Some(History::default()),
+                    ..config
+                },
+            );
+            sim.add_clock(&port_c, Clock::new(10))?;
+            sim.set(&port_d, [1]);
+
+            let id_changes = sim.watch(Watchpoint::changes("q"))?;
+            let id_equals = sim.watch(Watchpoint::equals("q", &[1, 1, 1]))?;
+
+            let mut hit_frames = Vec::new();
+            while let Some(halt) = sim.run_cycles(&port_c, 10)? {
+                let HaltReason::Watchpoints(hits) = halt.reason else {
+                    panic!("{:?}", halt);
+                };
+                let ids: Vec<usize> = hits.iter().map(|it| it.id).collect();
+                hit_frames.push((halt.frame, ids));
+            }
+            assert_eq!(
+                hit_frames,
+                [
+                    (2, vec![id_changes]),
+                    (4, vec![id_changes]),
+                    (6, vec![id_changes, id_equals])
+                ]
+            );
+
+            // going back in time does not fire, simulating the same frames again does
+            sim.goto_frame(3)?;
+            assert!(sim.watch_hits().is_empty());
+            let halt = sim.run_for(100)?.unwrap();
+            assert_eq!(halt.frame, 4);
+            assert_eq!(sim.watch_hits()[0].value, [Logic::_1, Logic::_1, Logic::X]);
+
+            assert_eq!(sim.unwatch(id_changes)?, Watchpoint::changes("q"));
+            assert_eq!(sim.watchpoints().count(), 1);
+            assert_eq!(sim.run_for(100)?.unwrap().frame, 6);
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_watchpoint_errors() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Shift")?;
+        let mut sim = Sim::new(module);
+
+        let err = sim.watch(Watchpoint::changes("p")).unwrap_err();
+        assert_eq!(err.code(), "NOT_FOUND");
+
+        let err = sim.watch(Watchpoint::equals("q", &[1])).unwrap_err();
+        assert_eq!(err.code(), "NET_WIDTH_MISMATCH");
+
+        let id = sim.watch(Watchpoint::changes("q"))?;
+        sim.unwatch(id)?;
+        let err = sim.unwatch(id).unwrap_err();
+        assert_eq!(err.code(), "NOT_FOUND");
+
+        Ok(())
+    })()
+    .unwrap()
+}
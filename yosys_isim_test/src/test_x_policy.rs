@@ -0,0 +1,181 @@
+use crate::TEST_GATES_SV;
+use crate::util::differential::assert_same_outputs;
+use std::ops::Deref;
+use yosys_isim::common::FindByName;
+use yosys_isim::common::SimError;
+use yosys_isim::sim::Engine;
+use yosys_isim::sim::Logic;
+use yosys_isim::sim::RegisterInit;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+use yosys_isim::sim::XPolicy;
+
+#[test]
+pub fn test_x_policies() {
+    (|| -> Result<(), SimError> {
+        use Logic::_0;
+        use Logic::_1;
+        use Logic::X;
+
+        #[rustfmt::skip]
+        let cases = [
+            // module, a,  b,  precise, pessimistic, two state
+            ("And",    _0, X,  _0,      X,           _0),
+            ("And",    _1, X,  X,       X,           _0),
+            ("Or",     _1, X,  _1,      X,           _1),
+            ("Or",     X,  X,  X,       X,           _0),
+            ("Nand",   X,  _0, _1,      X,           _1),
+            ("Nor",    X,  _0, X,       X,           _1),
+        ];
+
+        for (module_name, a, b, precise, pessimistic, two_state) in cases {
+            let module = TEST_GATES_SV.deref().iter().find_by_name(module_name)?;
+            let port_a = module.get_in_port::<1>("a")?;
+            let port_b = module.get_in_port::<1>("b")?;
+            let port_y = module.get_out_port::<1>("y")?;
+
+            for (x_policy, expected) in [
+                (XPolicy::Precise, precise),
+                (XPolicy::Pessimistic, pessimistic),
+                (XPolicy::TwoState, two_state),
+            ] {
+                let mut sim = Sim::with_config(
+                    module,
+                    SimConfig {
+                        x_policy,
+                        ..Default::default()
+                    },
+                );
+                sim.set(&port_a, [a]);
+                sim.set(&port_b, [b]);
+                sim.simulate()?;
+                assert_eq!(
+                    sim.get(&port_y),
+                    [expected],
+                    "{}({:?}, {:?}) with {:?}",
+                    module_name,
+                    a,
+                    b,
+                    x_policy
+                );
+            }
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_two_state_random() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("And")?;
+        let port_a = module.get_in_port::<1>("a")?;
+        let port_b = module.get_in_port::<1>("b")?;
+        let port_y = module.get_out_port::<1>("y")?;
+
+        let mut simulate = |seed: u64| -> Result<Logic, SimError> {
+            let mut sim = Sim::with_config(
+                module,
+                SimConfig {
+                    x_policy: XPolicy::TwoStateRandom { seed },
+                    ..Default::default()
+                },
+            );
+            sim.set(&port_a, [Logic::_1]);
+            sim.set(&port_b, [Logic::X]);
+            sim.simulate()?;
+            Ok(sim.get(&port_y)[0])
+        };
+
+        // `1 & X` is `0` or `1`, the same for the same seed
+        let logics: Vec<Logic> = (0..16).map(&mut simulate).collect::<Result<_, _>>()?;
+        assert!(logics.contains(&Logic::_0));
+        assert!(logics.contains(&Logic::_1));
+        for (seed, logic) in logics.iter().enumerate() {
+            assert_eq!(simulate(seed as u64)?, *logic);
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_two_state_init() {
+    (|| -> Result<(), SimError> {
+        let module = TEST_GATES_SV.deref().iter().find_by_name("Blocks")?;
+        let port_x = module.get_out_port::<4>("x")?;
+        let port_y = module.get_out_port::<4>("y")?;
+
+        let mut sim = Sim::with_config(
+            module,
+            SimConfig {
+                x_policy: XPolicy::TwoState,
+                ..Default::default()
+            },
+        );
+        assert_eq!(sim.get(&port_x), [Logic::_0; 4]);
+
+        // the adder reads the unset in port `a` as 0
+        let port_c = module.get_in_port::<1>("c")?;
+        sim.set(&port_c, [0]);
+        sim.simulate()?;
+        sim.set(&port_c, [1]);
+        sim.simulate()?;
+        assert_eq!(sim.get(&port_x), [Logic::_0; 4]);
+
+        for seed in 0..8 {
+            let mut sim = Sim::with_config(
+                module,
+                SimConfig {
+                    x_policy: XPolicy::TwoState,
+                    register_init: RegisterInit::Random { seed },
+                    ..Default::default()
+                },
+            );
+            assert!(!sim.get(&port_x).contains(&Logic::X));
+            assert!(!sim.get(&port_y).contains(&Logic::X));
+
+            let mut sim = Sim::with_config(
+                module,
+                SimConfig {
+                    x_policy: XPolicy::TwoStateRandom { seed },
+                    ..Default::default()
+                },
+            );
+            assert!(!sim.get(&port_x).contains(&Logic::X));
+        }
+
+        Ok(())
+    })()
+    .unwrap()
+}
+
+#[test]
+pub fn test_engines_match_with_x_policy() {
+    for module in TEST_GATES_SV.deref().iter() {
+        for x_policy in [
+            XPolicy::Pessimistic,
+            XPolicy::TwoState,
+            XPolicy::TwoStateRandom { seed: 7 },
+        ] {
+            for engine in [Engine::Tape, Engine::Jit] {
+                assert_same_outputs(
+                    module,
+                    SimConfig {
+                        x_policy,
+                        ..Default::default()
+                    },
+                    SimConfig {
+                        engine,
+                        x_policy,
+                        ..Default::default()
+                    },
+                    0xd1b5_4a32_d192_ed03,
+                )
+                .unwrap();
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use yosys_isim::common::Vec4;
 use yosys_isim::sim::Logic;
+use yosys_isim::sim::LogicVec;
 
 pub fn assert(
     name: &str,
@@ -19,9 +20,16 @@ pub fn assert(
 
 fn assert_monomorphic(name: &str, input: Vec4<Logic>, actual: Vec4<Logic>, expected: Vec4<Logic>) {
     if actual != expected {
+        let input: Vec<String> = input
+            .iter()
+            .map(|it| LogicVec(vec![*it]).to_string())
+            .collect();
         panic!(
-            "Error: {}({:?}) = {:?}, actually expected {:?}",
-            name, input, actual, expected
+            "Error: {}({}) = {}, actually expected {}",
+            name,
+            input.join(", "),
+            LogicVec(actual.to_vec()),
+            LogicVec(expected.to_vec())
         );
     }
 }
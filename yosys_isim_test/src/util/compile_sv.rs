@@ -5,13 +5,14 @@ use yosys_isim::json::parse_modules_from_file;
 use yosys_isim::model::Module;
 
 pub fn compile(sv_file: &str) -> Vec<Module> {
-    match do_compile(sv_file) {
+    match compile_netlist(sv_file).and_then(|netlist_file| parse_modules_from_file(&netlist_file)) {
         Ok(modules) => modules,
         Err(err) => panic!("{:?}", err),
     }
 }
 
-fn do_compile(sv_file: &str) -> Result<Vec<Module>, SimError> {
+/// runs yosys on `sv_file` and returns the path of the written netlist.json
+pub fn compile_netlist(sv_file: &str) -> Result<String, SimError> {
     let netlist_file = sv_file.replace("/", "_");
 
     fs::create_dir_all("target")?;
@@ -27,5 +28,5 @@ fn do_compile(sv_file: &str) -> Result<Vec<Module>, SimError> {
     ]);
     yosys.spawn()?.wait()?;
 
-    parse_modules_from_file(&format!("target/{}.json", netlist_file))
+    Ok(format!("target/{}.json", netlist_file))
 }
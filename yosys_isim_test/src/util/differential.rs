@@ -0,0 +1,46 @@
+use crate::util::random::Stimulus;
+use yosys_isim::common::Random;
+use yosys_isim::common::SimError;
+use yosys_isim::model::Module;
+use yosys_isim::sim::Sim;
+use yosys_isim::sim::SimConfig;
+
+/// Drives a [Sim] with `expected` and one with `actual` with the same random `0`, `1` and `X`
+/// stimulus for 64 frames and asserts that their out ports match after every frame.
+pub fn assert_same_outputs(
+    module: &Module,
+    expected: SimConfig,
+    actual: SimConfig,
+    seed: u64,
+) -> Result<(), SimError> {
+    let description = format!("{:?} and {:?}", expected, actual);
+    let mut expected = Sim::with_config(module, expected);
+    let mut actual = Sim::with_config(module, actual);
+
+    let mut random = Random::new(seed);
+    for frame in 0..64 {
+        for port in module.in_ports.iter() {
+            let logics = random.logics_with_x(port.wires.len());
+
+            expected.set_dynamic(port, &logics);
+            actual.set_dynamic(port, &logics);
+        }
+
+        expected.simulate()?;
+        actual.simulate()?;
+
+        for port in module.out_ports.iter() {
+            assert_eq!(
+                actual.get_dynamic(port),
+                expected.get_dynamic(port),
+                "{}.{} differs in frame {} with {}",
+                module.name,
+                port.name,
+                frame,
+                description
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,2 +1,6 @@
 pub mod assert;
 pub mod compile_sv;
+#[cfg(test)]
+pub mod differential;
+#[cfg(test)]
+pub mod random;
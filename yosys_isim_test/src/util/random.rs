@@ -0,0 +1,30 @@
+use yosys_isim::common::Random;
+use yosys_isim::sim::Logic;
+
+/// The random stimulus of the tests, drawn from the seeded [Random] of the crate.
+pub trait Stimulus {
+    /// `width` values of `0` or `1`
+    fn bits(&mut self, width: usize) -> Vec<u64>;
+
+    /// `width` logics, a quarter of them `X`
+    fn logics_with_x(&mut self, width: usize) -> Vec<Logic>;
+}
+
+impl Stimulus for Random {
+    fn bits(&mut self, width: usize) -> Vec<u64> {
+        (0..width).map(|_| self.next_u64() >> 63).collect()
+    }
+
+    fn logics_with_x(&mut self, width: usize) -> Vec<Logic> {
+        (0..width)
+            .map(|_| {
+                let value = self.next_u64();
+                match value >> 62 {
+                    0 => Logic::X,
+                    1 | 2 => Logic::from((value >> 61) & 1),
+                    _ => Logic::_1,
+                }
+            })
+            .collect()
+    }
+}